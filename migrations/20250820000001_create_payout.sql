@@ -0,0 +1,22 @@
+-- 审核通过后的链上奖励发放记录，每个提交最多一条
+CREATE TABLE `payout` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `submission_id` bigint(20) NOT NULL,
+  `user_id` bigint(20) NOT NULL,
+  `recipient_address` varchar(66) DEFAULT NULL,
+  `coin_type` varchar(255) NOT NULL,
+  `amount` bigint(20) unsigned NOT NULL,
+  `status` varchar(32) NOT NULL DEFAULT 'pending',
+  `tx_digest` varchar(64) DEFAULT NULL,
+  `error` varchar(512) NOT NULL DEFAULT '',
+  `attempts` int(11) NOT NULL DEFAULT '0',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  `confirmed_at` timestamp NULL DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `submission_id` (`submission_id`),
+  KEY `user_id` (`user_id`),
+  KEY `status` (`status`),
+  CONSTRAINT `payout_ibfk_1` FOREIGN KEY (`submission_id`) REFERENCES `task_submission` (`id`),
+  CONSTRAINT `payout_ibfk_2` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
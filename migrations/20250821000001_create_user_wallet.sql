@@ -0,0 +1,28 @@
+-- 用户绑定的 SUI 收款地址，地址需通过个人消息签名证明归属
+CREATE TABLE `user_wallet` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `user_id` bigint(20) NOT NULL,
+  `address` varchar(66) NOT NULL,
+  `is_primary` tinyint(1) NOT NULL DEFAULT '0',
+  `verified_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `address` (`address`),
+  KEY `user_id` (`user_id`),
+  CONSTRAINT `user_wallet_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 绑定钱包时下发的一次性挑战，保存完整消息以便校验签名
+CREATE TABLE `wallet_challenge` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `user_id` bigint(20) NOT NULL,
+  `nonce` char(32) NOT NULL,
+  `message` varchar(512) NOT NULL,
+  `expires_at` timestamp NOT NULL,
+  `used_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `nonce` (`nonce`),
+  KEY `user_id` (`user_id`),
+  CONSTRAINT `wallet_challenge_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,22 @@
+-- 提交状态变更历史，每次状态转换写入一条记录
+CREATE TABLE `task_submission_event` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `submission_id` bigint(20) NOT NULL,
+  `actor_id` bigint(20) DEFAULT NULL,
+  `from_status` varchar(32) DEFAULT NULL,
+  `to_status` varchar(32) NOT NULL,
+  `comment` varchar(512) NOT NULL DEFAULT '',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `submission_id` (`submission_id`),
+  KEY `actor_id` (`actor_id`),
+  CONSTRAINT `task_submission_event_ibfk_1` FOREIGN KEY (`submission_id`) REFERENCES `task_submission` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `task_submission_event_ibfk_2` FOREIGN KEY (`actor_id`) REFERENCES `sys_user` (`id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 为已有提交补齐历史：创建记录，以及审核结果（审核人未知）
+INSERT INTO `task_submission_event` (`submission_id`, `actor_id`, `from_status`, `to_status`, `created_at`)
+SELECT `id`, `user_id`, NULL, 'pending', `created_at` FROM `task_submission`;
+
+INSERT INTO `task_submission_event` (`submission_id`, `actor_id`, `from_status`, `to_status`, `created_at`)
+SELECT `id`, NULL, 'pending', `status`, `updated_at` FROM `task_submission` WHERE `status` <> 'pending';
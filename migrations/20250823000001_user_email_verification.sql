@@ -0,0 +1,20 @@
+-- 自助注册：邮箱及验证状态；sys_user.status 取值 0 禁用 / 1 正常 / 2 待验证邮箱
+ALTER TABLE `sys_user`
+  ADD COLUMN `email` varchar(255) DEFAULT NULL AFTER `username`,
+  ADD COLUMN `email_verified_at` timestamp NULL DEFAULT NULL AFTER `email`,
+  ADD UNIQUE KEY `email` (`email`);
+
+-- 一次性用户令牌（邮箱验证等），只保存 SHA-256 摘要
+CREATE TABLE `sys_user_token` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `user_id` bigint(20) NOT NULL,
+  `purpose` varchar(32) NOT NULL,
+  `token_hash` char(64) NOT NULL,
+  `expires_at` timestamp NOT NULL,
+  `used_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `token_hash` (`token_hash`),
+  KEY `user_id` (`user_id`, `purpose`),
+  CONSTRAINT `sys_user_token_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
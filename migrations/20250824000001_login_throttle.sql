@@ -0,0 +1,15 @@
+-- 账号锁定：连续登录失败次数和锁定截止时间
+ALTER TABLE `sys_user`
+  ADD COLUMN `failed_login_count` int(11) NOT NULL DEFAULT '0' AFTER `status`,
+  ADD COLUMN `locked_until` timestamp NULL DEFAULT NULL AFTER `failed_login_count`;
+
+-- 按用户名 / IP 统计的登录失败次数，用于指数退避
+CREATE TABLE `sys_login_throttle` (
+  `scope` varchar(16) NOT NULL,
+  `subject` varchar(255) NOT NULL,
+  `failures` int(11) NOT NULL DEFAULT '0',
+  `last_failed_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `blocked_until` timestamp NULL DEFAULT NULL,
+  PRIMARY KEY (`scope`, `subject`),
+  KEY `last_failed_at` (`last_failed_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,22 @@
+-- TOTP 两步验证：enabled_at 为空表示已生成密钥但尚未确认
+CREATE TABLE `sys_user_totp` (
+  `user_id` bigint(20) NOT NULL,
+  `secret` varchar(64) NOT NULL,
+  `enabled_at` timestamp NULL DEFAULT NULL,
+  `last_used_step` bigint(20) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`user_id`),
+  CONSTRAINT `sys_user_totp_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 恢复码，只保存 SHA-256 摘要，每个只能使用一次
+CREATE TABLE `sys_user_recovery_code` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `user_id` bigint(20) NOT NULL,
+  `code_hash` char(64) NOT NULL,
+  `used_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `user_code` (`user_id`, `code_hash`),
+  CONSTRAINT `sys_user_recovery_code_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
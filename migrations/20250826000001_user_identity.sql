@@ -0,0 +1,28 @@
+-- 第三方登录账号与本地用户的绑定
+CREATE TABLE `user_identity` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `user_id` bigint(20) NOT NULL,
+  `provider` varchar(32) NOT NULL,
+  `provider_user_id` varchar(64) NOT NULL,
+  `provider_login` varchar(255) NOT NULL,
+  `email` varchar(255) DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `provider_user` (`provider`, `provider_user_id`),
+  UNIQUE KEY `user_provider` (`user_id`, `provider`),
+  CONSTRAINT `user_identity_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- OAuth 授权请求的 state，只保存 SHA-256 摘要；user_id 不为空表示为已登录用户绑定账号
+CREATE TABLE `oauth_state` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `provider` varchar(32) NOT NULL,
+  `state_hash` char(64) NOT NULL,
+  `user_id` bigint(20) DEFAULT NULL,
+  `expires_at` timestamp NOT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `state_hash` (`state_hash`),
+  CONSTRAINT `oauth_state_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
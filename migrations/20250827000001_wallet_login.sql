@@ -0,0 +1,5 @@
+-- 钱包登录：登录挑战不属于任何用户，记录待签名的地址
+ALTER TABLE `wallet_challenge`
+  MODIFY COLUMN `user_id` bigint(20) DEFAULT NULL,
+  ADD COLUMN `address` varchar(66) DEFAULT NULL AFTER `user_id`,
+  ADD KEY `expires_at` (`expires_at`);
@@ -0,0 +1,8 @@
+-- 内置角色和权限不可修改或删除，代码中的路由依赖这些权限编码
+ALTER TABLE `sys_role` ADD COLUMN `is_builtin` tinyint(1) NOT NULL DEFAULT '0' AFTER `status`;
+ALTER TABLE `sys_permission` ADD COLUMN `is_builtin` tinyint(1) NOT NULL DEFAULT '0' AFTER `perm_name`;
+
+UPDATE `sys_role` SET `is_builtin` = 1 WHERE `role_key` IN ('admin', 'user');
+UPDATE `sys_permission` SET `is_builtin` = 1 WHERE `perm_key` IN (
+  'admin:access', 'task:create', 'task:update', 'task:delete', 'submission:review', 'payout:read', 'payout:manage'
+);
@@ -0,0 +1,4 @@
+-- 用户可自行修改的资料字段
+ALTER TABLE `sys_user`
+  ADD COLUMN `display_name` varchar(64) DEFAULT NULL AFTER `username`,
+  ADD COLUMN `avatar_url` varchar(512) DEFAULT NULL AFTER `email_verified_at`;
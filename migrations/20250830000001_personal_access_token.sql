@@ -0,0 +1,25 @@
+-- 个人访问令牌：只保存 SHA-256 摘要，token_prefix 用于在列表中辨认令牌
+CREATE TABLE `personal_access_token` (
+  `id` bigint(20) NOT NULL AUTO_INCREMENT,
+  `user_id` bigint(20) NOT NULL,
+  `name` varchar(64) NOT NULL,
+  `token_prefix` varchar(16) NOT NULL,
+  `token_hash` char(64) NOT NULL,
+  `expires_at` timestamp NULL DEFAULT NULL,
+  `last_used_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `token_hash` (`token_hash`),
+  KEY `user_id` (`user_id`),
+  CONSTRAINT `personal_access_token_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 令牌范围对应 sys_permission 中的权限编码
+CREATE TABLE `personal_access_token_scope` (
+  `token_id` bigint(20) NOT NULL,
+  `perm_id` bigint(20) NOT NULL,
+  PRIMARY KEY (`token_id`, `perm_id`),
+  KEY `perm_id` (`perm_id`),
+  CONSTRAINT `personal_access_token_scope_ibfk_1` FOREIGN KEY (`token_id`) REFERENCES `personal_access_token` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `personal_access_token_scope_ibfk_2` FOREIGN KEY (`perm_id`) REFERENCES `sys_permission` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
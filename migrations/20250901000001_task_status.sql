@@ -0,0 +1,7 @@
+-- 任务生命周期：draft / published / closed / archived，归档代替删除
+ALTER TABLE `task`
+  ADD COLUMN `status` varchar(32) NOT NULL DEFAULT 'draft' AFTER `description`,
+  ADD KEY `status` (`status`);
+
+-- 已有任务保持对学员可见
+UPDATE `task` SET `status` = 'published';
@@ -0,0 +1,18 @@
+-- 任务开放时间窗口，到达 closes_at 后由后台任务自动关闭
+ALTER TABLE `task`
+  ADD COLUMN `opens_at` timestamp NULL DEFAULT NULL AFTER `status`,
+  ADD COLUMN `closes_at` timestamp NULL DEFAULT NULL AFTER `opens_at`,
+  ADD KEY `closes_at` (`closes_at`);
+
+-- 学员的个人截止时间，与任务的 closes_at 按较早者为准
+CREATE TABLE `task_deadline` (
+  `task_id` bigint(20) NOT NULL,
+  `user_id` bigint(20) NOT NULL,
+  `due_at` timestamp NOT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`task_id`, `user_id`),
+  KEY `user_id` (`user_id`),
+  CONSTRAINT `task_deadline_ibfk_1` FOREIGN KEY (`task_id`) REFERENCES `task` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `task_deadline_ibfk_2` FOREIGN KEY (`user_id`) REFERENCES `sys_user` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,5 @@
+-- 钱包登录挑战记录请求方 IP，按 IP 和地址限制未使用的挑战数量
+ALTER TABLE `wallet_challenge`
+  ADD COLUMN `client_ip` varchar(45) DEFAULT NULL AFTER `address`,
+  ADD KEY `address` (`address`),
+  ADD KEY `client_ip` (`client_ip`);
@@ -0,0 +1,37 @@
+-- 初始数据：角色、权限、默认用户和示例任务
+-- 可重复执行，已存在的记录会被跳过
+
+INSERT IGNORE INTO `sys_permission` (`id`, `perm_key`, `perm_name`, `is_builtin`) VALUES
+  (1, 'admin:access', 'Admin Access', 1),
+  (2, 'task:create', 'Create Task', 1),
+  (3, 'task:update', 'Update Task', 1),
+  (4, 'task:delete', 'Delete Task', 1),
+  (5, 'submission:review', 'Review Submission', 1),
+  (6, 'payout:read', 'View Payouts', 1),
+  (7, 'payout:manage', 'Manage Payouts', 1);
+
+INSERT IGNORE INTO `sys_role` (`id`, `role_key`, `role_name`, `is_builtin`) VALUES
+  (1, 'admin', 'Administrator', 1),
+  (2, 'user', 'User', 1);
+
+INSERT IGNORE INTO `sys_role_perm` (`role_id`, `perm_id`) VALUES
+  (1, 1), (1, 2), (1, 3), (1, 4), (1, 5), (1, 6), (1, 7);
+
+-- admin/admin123, demo/user123
+INSERT IGNORE INTO `sys_user` (`id`, `username`, `password_hash`, `salt`) VALUES
+  (1, 'admin', '$2b$12$YVRSGHQMhjjdcBXDs8XKAeoyB7wolGv2rixqo4HnMB3r7haOxiU0C', 'Ae4HWPHbl2ofbZtI'),
+  (2, 'demo', '$2b$12$ItKfDeXfz2YWv9xjLOoiMeEPw4/oY5h2fn2DXOc61txeolHImzLaK', 'Xq7Lm2Rt9Vb4Nc1K');
+
+INSERT IGNORE INTO `sys_user_role` (`user_id`, `role_id`) VALUES
+  (1, 1),
+  (2, 2);
+
+INSERT IGNORE INTO `task` (`id`, `code`, `name`, `reward_cny`, `reward_token`, `description`, `status`) VALUES
+  (1, 'task_1', 'hello move', 10, '', '完成第一个合约部署上链', 'published'),
+  (2, 'task_2', 'move coin', 10, '', '完成Coin协议学习，并发布两个Coin上链', 'published'),
+  (3, 'task_3', 'move nft', 10, '', '完成NFT的学习，并发布NFT上链', 'published'),
+  (4, 'task_4', 'move game', 10, '', '完成链上游戏学习，并上链交互', 'published'),
+  (5, 'task_5', 'move swap', 10, '', '完成Swap学习，并上链交互', 'published'),
+  (6, 'task_6', 'sdk ptb', 10, 'NAVX', '完成SDK学习，并用SDK完成链上交互', 'published'),
+  (7, 'task_7', 'move ctf check in', 10, '', '完成move ctf check in', 'published'),
+  (8, 'task_8', 'move ctf pow', 10, '', '完成move ctf pow', 'published');
@@ -1,22 +1,572 @@
+use config::{Config, ConfigError, Environment, File, FileFormat, Map, Source, Value};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+// 开发环境默认的 JWT 密钥，仅允许在 dev 模式下使用
+pub const DEFAULT_JWT_SECRET: &str = "your-secret-key";
+
+// 配置文件路径可通过 L2E_CONFIG 指定，默认读取项目根目录下的 l2e.toml（可选）
+const DEFAULT_CONFIG_FILE: &str = "l2e.toml";
+
+// 旧版 .env 中使用的环境变量及对应的配置项，升级后仍然生效
+const LEGACY_ENV_VARS: [(&str, &str); 4] = [
+    ("DATABASE_URL", "database.url"),
+    ("DB_MAX_CONNECTIONS", "database.max_connections"),
+    ("DB_MIN_CONNECTIONS", "database.min_connections"),
+    ("JWT_SECRET", "jwt.secret"),
+];
+
+// 把旧版环境变量映射到新的配置项，优先级高于配置文件、低于 L2E_* 环境变量
+#[derive(Debug, Clone)]
+struct LegacyEnvironment;
+
+impl Source for LegacyEnvironment {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let origin = "legacy environment".to_string();
+        Ok(LEGACY_ENV_VARS
+            .iter()
+            .filter_map(|(var, key)| {
+                let value = std::env::var(var).ok()?;
+                Some((key.to_string(), Value::new(Some(&origin), value)))
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunMode {
+    Dev,
+    Prod,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub mode: RunMode,
+    pub server: ServerSettings,
+    pub database: DatabaseSettings,
+    pub jwt: JwtSettings,
+    pub auth: AuthSettings,
+    pub mail: MailSettings,
+    pub payout: PayoutSettings,
+    pub oauth: OAuthSettings,
+    pub tasks: TaskSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    pub workers: Option<usize>, // 未设置时使用 CPU 核数
+    pub cors_origins: Vec<String>,
+    pub public_url: String, // 对外访问地址，用于邮件中的链接
+    pub behind_proxy: bool, // 部署在反向代理之后时，从 X-Forwarded-For 读取客户端 IP
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct DatabaseSettings {
     pub url: String,
     pub max_connections: u32,
     pub min_connections: u32,
+    pub run_migrations: bool, // 启动时执行 migrations/ 下的迁移
+    pub seed: bool,           // 启动时写入 sql/seed.sql 初始数据
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JwtSettings {
+    pub secret: String, // 未配置 key_dir 时使用 HS256 签名
+    pub key_dir: String, // 非空时使用目录下的 RS256 / EdDSA 密钥签名，文件名即 kid
+    pub signing_kid: String, // 当前用于签名的密钥
+    pub access_ttl_minutes: i64,
+    pub refresh_ttl_days: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AuthSettings {
+    pub allow_registration: bool,
+    pub verification_ttl_hours: i64, // 邮箱验证链接有效期
+    pub password_reset_ttl_minutes: i64,
+    pub reset_default_passwords: bool, // 启动时把 admin/demo 密码重置为默认值，仅用于本地开发
+    pub wallet_login: bool, // 允许使用已绑定的 SUI 钱包签名登录
+    pub login: LoginThrottleSettings,
+    pub two_factor: TwoFactorSettings,
+}
+
+// 登录失败限制：同一用户名 / IP 连续失败超过 backoff_after 次后按指数退避，
+// 同一账号累计失败 lockout_threshold 次后锁定 lockout_minutes 分钟
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoginThrottleSettings {
+    pub backoff_after: u32,
+    pub ip_backoff_after: u32, // 同一 IP 可能对应多个用户，阈值更宽松
+    pub backoff_base_seconds: u64,
+    pub backoff_max_seconds: u64,
+    pub failure_window_minutes: i64, // 超过该时间没有失败则重新计数
+    pub lockout_threshold: u32,
+    pub lockout_minutes: i64,
+}
+
+// TOTP 两步验证
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TwoFactorSettings {
+    pub issuer: String, // 显示在验证器 App 中的名称
+    pub challenge_ttl_minutes: i64, // 密码验证通过后提交验证码的期限
+    pub require_for_reviewers: bool, // 持有 submission:review 的用户必须启用两步验证
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TaskSettings {
+    pub close_interval_seconds: u64, // 检查并关闭到期任务的间隔
+}
+
+// 第三方登录
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OAuthSettings {
+    pub github: GithubOAuthSettings,
+}
+
+// GitHub OAuth App；各端点可改为本地 mock 服务便于测试
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GithubOAuthSettings {
+    pub enabled: bool,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_url: String, // 前端回调页面，需与 OAuth App 中配置的一致
+    pub scopes: Vec<String>,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub user_url: String,
+    pub emails_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MailBackend {
+    // 只打印到日志
+    Log,
+    // 写入 file_dir 下的 .eml 文件
+    File,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MailSettings {
+    pub backend: MailBackend,
+    pub from: String,
+    pub file_dir: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutBackend {
+    // 内存 mock 节点，用于本地开发和测试
+    Mock,
+    // SUI 全节点 JSON-RPC
+    JsonRpc,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PayoutSettings {
+    pub enabled: bool,
+    pub backend: PayoutBackend,
+    pub rpc_url: String,
+    pub signer_key: String, // base64 编码的 Ed25519 私钥
+    pub gas_budget: u64,
+    pub default_token: String, // 任务未设置 reward_token 时使用
+    pub tokens: HashMap<String, PayoutTokenSettings>,
+    pub recover_interval_seconds: u64,   // 检查中断发放的间隔
+    pub processing_timeout_seconds: u64, // 超过该时间仍处于 processing 的发放视为中断
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayoutTokenSettings {
+    pub coin_type: String,
+    pub amount_per_cny: u64, // 每 1 元奖励对应的链上最小单位数量
+}
+
+impl PayoutSettings {
+    // 代币符号不区分大小写
+    pub fn token(&self, symbol: &str) -> Option<&PayoutTokenSettings> {
+        self.tokens
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(symbol))
+            .map(|(_, token)| token)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            mode: RunMode::Prod, // 未配置时按生产环境校验，缺少配置会拒绝启动
+            server: ServerSettings::default(),
+            database: DatabaseSettings::default(),
+            jwt: JwtSettings::default(),
+            auth: AuthSettings::default(),
+            mail: MailSettings::default(),
+            payout: PayoutSettings::default(),
+            oauth: OAuthSettings::default(),
+            tasks: TaskSettings::default(),
+        }
+    }
+}
+
+impl Default for PayoutSettings {
+    fn default() -> Self {
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            "SUI".to_string(),
+            PayoutTokenSettings {
+                coin_type: "0x2::sui::SUI".to_string(),
+                amount_per_cny: 50_000_000,
+            },
+        );
+
+        PayoutSettings {
+            enabled: false,
+            backend: PayoutBackend::Mock,
+            rpc_url: "https://fullnode.testnet.sui.io:443".to_string(),
+            signer_key: String::new(),
+            gas_budget: 10_000_000,
+            default_token: "SUI".to_string(),
+            tokens,
+            recover_interval_seconds: 60,
+            processing_timeout_seconds: 300,
+        }
+    }
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        ServerSettings {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            workers: None,
+            cors_origins: vec![
+                "http://localhost:5173".to_string(),
+                "http://127.0.0.1:5173".to_string(),
+            ],
+            public_url: "http://127.0.0.1:8080".to_string(),
+            behind_proxy: false,
+        }
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        DatabaseSettings {
+            url: String::new(),
+            max_connections: 10,
+            min_connections: 1,
+            run_migrations: false,
+            seed: false,
+        }
+    }
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        AuthSettings {
+            allow_registration: true,
+            verification_ttl_hours: 24,
+            password_reset_ttl_minutes: 30,
+            reset_default_passwords: false,
+            wallet_login: true,
+            login: LoginThrottleSettings::default(),
+            two_factor: TwoFactorSettings::default(),
+        }
+    }
+}
+
+impl Default for LoginThrottleSettings {
+    fn default() -> Self {
+        LoginThrottleSettings {
+            backoff_after: 3,
+            ip_backoff_after: 20,
+            backoff_base_seconds: 1,
+            backoff_max_seconds: 300,
+            failure_window_minutes: 60,
+            lockout_threshold: 10,
+            lockout_minutes: 15,
+        }
+    }
+}
+
+impl Default for TwoFactorSettings {
+    fn default() -> Self {
+        TwoFactorSettings {
+            issuer: "Learn2Earn".to_string(),
+            challenge_ttl_minutes: 5,
+            require_for_reviewers: false,
+        }
+    }
+}
+
+impl Default for GithubOAuthSettings {
+    fn default() -> Self {
+        GithubOAuthSettings {
+            enabled: false,
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_url: String::new(),
+            scopes: vec!["read:user".to_string(), "user:email".to_string()],
+            authorize_url: "https://github.com/login/oauth/authorize".to_string(),
+            token_url: "https://github.com/login/oauth/access_token".to_string(),
+            user_url: "https://api.github.com/user".to_string(),
+            emails_url: "https://api.github.com/user/emails".to_string(),
+        }
+    }
+}
+
+impl Default for TaskSettings {
+    fn default() -> Self {
+        TaskSettings {
+            close_interval_seconds: 60,
+        }
+    }
+}
+
+impl Default for MailSettings {
+    fn default() -> Self {
+        MailSettings {
+            backend: MailBackend::Log,
+            from: "Learn2Earn <no-reply@learn2earn.local>".to_string(),
+            file_dir: "tmp/mail".to_string(),
+        }
+    }
+}
+
+impl Default for JwtSettings {
+    fn default() -> Self {
+        JwtSettings {
+            secret: DEFAULT_JWT_SECRET.to_string(),
+            key_dir: String::new(),
+            signing_kid: String::new(),
+            access_ttl_minutes: 15,
+            refresh_ttl_days: 7,
+        }
+    }
 }
 
-impl DatabaseSettings {
-    pub fn from_env() -> Self {
-        Self {
-            url: std::env::var("DATABASE_URL")
-                .expect("DATABASE_URL must be set"),
-            max_connections: std::env::var("DB_MAX_CONNECTIONS")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .expect("DB_MAX_CONNECTIONS must be a valid number"),
-            min_connections: std::env::var("DB_MIN_CONNECTIONS")
-                .unwrap_or_else(|_| "1".to_string())
-                .parse()
-                .expect("DB_MIN_CONNECTIONS must be a valid number"),
+// 配置加载或校验失败，启动时一次性报告所有问题
+#[derive(Debug)]
+pub enum SettingsError {
+    Load(ConfigError),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Load(e) => write!(f, "failed to load configuration: {}", e),
+            SettingsError::Invalid(problems) => {
+                writeln!(f, "invalid configuration:")?;
+                for problem in problems {
+                    writeln!(f, "  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl Settings {
+    // 依次叠加：默认值 -> 配置文件 -> 旧版环境变量 -> L2E_* 环境变量（如 L2E_SERVER__PORT=9000）
+    pub fn load() -> Result<Self, SettingsError> {
+        let config_file = std::env::var("L2E_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+
+        for (var, key) in LEGACY_ENV_VARS {
+            if std::env::var_os(var).is_some() {
+                eprintln!(
+                    "warning: {} is deprecated, use L2E_{} instead",
+                    var,
+                    key.to_uppercase().replace('.', "__")
+                );
+            }
+        }
+
+        let settings: Settings = Config::builder()
+            .add_source(File::new(&config_file, FileFormat::Toml).required(false))
+            .add_source(LegacyEnvironment)
+            .add_source(
+                Environment::with_prefix("L2E")
+                    .prefix_separator("_")
+                    .separator("__")
+                    .list_separator(",")
+                    .with_list_parse_key("server.cors_origins")
+                    .with_list_parse_key("oauth.github.scopes")
+                    .try_parsing(true),
+            )
+            .build()
+            .and_then(|config| config.try_deserialize())
+            .map_err(SettingsError::Load)?;
+
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn is_dev(&self) -> bool {
+        self.mode == RunMode::Dev
+    }
+
+    pub fn bind_address(&self) -> (String, u16) {
+        (self.server.host.clone(), self.server.port)
+    }
+
+    fn validate(&self) -> Result<(), SettingsError> {
+        let mut problems = Vec::new();
+
+        if self.server.host.trim().is_empty() {
+            problems.push("server.host must not be empty".to_string());
+        }
+        if self.server.workers == Some(0) {
+            problems.push("server.workers must be greater than 0".to_string());
+        }
+        if self.server.cors_origins.iter().any(|origin| origin.trim().is_empty()) {
+            problems.push("server.cors_origins must not contain empty entries".to_string());
+        }
+        if !self.server.public_url.starts_with("http://") && !self.server.public_url.starts_with("https://") {
+            problems.push("server.public_url must be an http(s) URL".to_string());
+        }
+
+        if self.database.url.trim().is_empty() {
+            problems.push("database.url must be set (L2E_DATABASE__URL)".to_string());
+        }
+        if self.database.max_connections == 0 {
+            problems.push("database.max_connections must be greater than 0".to_string());
+        }
+        if self.database.min_connections > self.database.max_connections {
+            problems.push("database.min_connections must not exceed database.max_connections".to_string());
+        }
+        // seed.sql 依赖最新迁移中的列，只能在迁移之后执行
+        if self.database.seed && !self.database.run_migrations {
+            problems.push("database.seed requires database.run_migrations".to_string());
+        }
+
+        if !self.jwt.key_dir.is_empty() {
+            if self.jwt.signing_kid.is_empty() {
+                problems.push("jwt.signing_kid must be set when jwt.key_dir is used".to_string());
+            }
+        } else if self.jwt.secret.is_empty() {
+            problems.push("jwt.secret must not be empty".to_string());
+        } else if !self.is_dev() && self.jwt.secret == DEFAULT_JWT_SECRET {
+            problems.push("jwt.secret must be changed from the default outside dev mode (L2E_JWT__SECRET)".to_string());
+        }
+        if self.jwt.access_ttl_minutes <= 0 {
+            problems.push("jwt.access_ttl_minutes must be greater than 0".to_string());
+        }
+        if self.jwt.refresh_ttl_days <= 0 {
+            problems.push("jwt.refresh_ttl_days must be greater than 0".to_string());
+        }
+
+        if self.auth.verification_ttl_hours <= 0 {
+            problems.push("auth.verification_ttl_hours must be greater than 0".to_string());
+        }
+        if self.auth.password_reset_ttl_minutes <= 0 {
+            problems.push("auth.password_reset_ttl_minutes must be greater than 0".to_string());
+        }
+        let login = &self.auth.login;
+        if login.backoff_after == 0 || login.ip_backoff_after == 0 {
+            problems.push("auth.login.backoff_after and auth.login.ip_backoff_after must be greater than 0".to_string());
+        }
+        if login.backoff_max_seconds < login.backoff_base_seconds {
+            problems.push("auth.login.backoff_max_seconds must not be less than auth.login.backoff_base_seconds".to_string());
+        }
+        if login.failure_window_minutes <= 0 {
+            problems.push("auth.login.failure_window_minutes must be greater than 0".to_string());
+        }
+        if login.lockout_threshold == 0 || login.lockout_minutes <= 0 {
+            problems.push("auth.login.lockout_threshold and auth.login.lockout_minutes must be greater than 0".to_string());
+        }
+        if self.auth.two_factor.issuer.trim().is_empty() {
+            problems.push("auth.two_factor.issuer must not be empty".to_string());
+        }
+        if self.auth.two_factor.challenge_ttl_minutes <= 0 {
+            problems.push("auth.two_factor.challenge_ttl_minutes must be greater than 0".to_string());
+        }
+        if !self.is_dev() && self.auth.reset_default_passwords {
+            problems.push("auth.reset_default_passwords must not be enabled outside dev mode".to_string());
+        }
+
+        if self.mail.from.trim().is_empty() {
+            problems.push("mail.from must not be empty".to_string());
+        }
+        if self.mail.backend == MailBackend::File && self.mail.file_dir.trim().is_empty() {
+            problems.push("mail.file_dir must be set for the file backend".to_string());
+        }
+
+        if self.payout.enabled {
+            if self.payout.backend == PayoutBackend::JsonRpc {
+                if self.payout.rpc_url.trim().is_empty() {
+                    problems.push("payout.rpc_url must be set for the json_rpc backend".to_string());
+                }
+                if self.payout.signer_key.trim().is_empty() {
+                    problems.push("payout.signer_key must be set for the json_rpc backend".to_string());
+                }
+            }
+            if !self.is_dev() && self.payout.backend == PayoutBackend::Mock {
+                problems.push("payout.backend must not be mock outside dev mode".to_string());
+            }
+            if self.payout.token(&self.payout.default_token).is_none() {
+                problems.push(format!(
+                    "payout.default_token '{}' has no entry in payout.tokens",
+                    self.payout.default_token
+                ));
+            }
+            if self.payout.gas_budget == 0 {
+                problems.push("payout.gas_budget must be greater than 0".to_string());
+            }
+            if self.payout.recover_interval_seconds == 0 {
+                problems.push("payout.recover_interval_seconds must be greater than 0".to_string());
+            }
+            if self.payout.processing_timeout_seconds == 0 {
+                problems.push("payout.processing_timeout_seconds must be greater than 0".to_string());
+            }
+        }
+
+        if self.tasks.close_interval_seconds == 0 {
+            problems.push("tasks.close_interval_seconds must be greater than 0".to_string());
+        }
+
+        let github = &self.oauth.github;
+        if github.enabled {
+            if github.client_id.trim().is_empty() || github.client_secret.trim().is_empty() {
+                problems.push("oauth.github.client_id and oauth.github.client_secret must be set (L2E_OAUTH__GITHUB__CLIENT_SECRET)".to_string());
+            }
+            let urls = [
+                ("redirect_url", &github.redirect_url),
+                ("authorize_url", &github.authorize_url),
+                ("token_url", &github.token_url),
+                ("user_url", &github.user_url),
+                ("emails_url", &github.emails_url),
+            ];
+            for (name, url) in urls {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    problems.push(format!("oauth.github.{} must be an http(s) URL", name));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SettingsError::Invalid(problems))
         }
     }
-}
\ No newline at end of file
+}
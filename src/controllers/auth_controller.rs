@@ -1,67 +1,263 @@
-use actix_web::{web, HttpResponse, Result, HttpRequest};
-use actix_web::HttpMessage;
-use crate::models::user::{CreateUserRequest, LoginRequest};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use crate::config::settings::Settings;
+use crate::error::AppError;
+use crate::models::session::ClientInfo;
+use crate::models::two_factor::TwoFactorLoginRequest;
+use crate::models::wallet::{WalletLoginChallengeQuery, WalletLoginRequest};
+use crate::models::user::{
+    CreateUserRequest, LoginRequest, RefreshTokenRequest, RegisterRequest, ResendVerificationRequest, VerifyEmailQuery,
+    ChangePasswordRequest, ForgotPasswordRequest, ResetPasswordRequest,
+};
 use crate::services::auth_service::AuthService;
+use crate::services::login_throttle_service::LoginThrottleService;
+use crate::services::permission_service::PermissionService;
+use crate::services::two_factor_service::TwoFactorService;
+use crate::services::wallet_service::WalletService;
 use crate::utils::jwt::Claims;
-use sqlx::MySqlPool;
+use crate::state::AppState;
+
+// 客户端 IP：仅在部署于反向代理之后时信任 X-Forwarded-For
+fn client_ip(req: &HttpRequest, settings: &Settings) -> Option<String> {
+    if settings.server.behind_proxy {
+        req.connection_info().realip_remote_addr().map(str::to_string)
+    } else {
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    }
+}
+
+// 登录和刷新令牌时记录到会话中的客户端信息
+pub fn client_info(req: &HttpRequest, settings: &Settings) -> ClientInfo {
+    ClientInfo {
+        ip: client_ip(req, settings),
+        user_agent: req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+    }
+}
 
 pub async fn login(
-    pool: web::Data<MySqlPool>,
+    req: HttpRequest,
+    state: web::Data<AppState>,
     login_data: web::Json<LoginRequest>,
-) -> Result<HttpResponse> {
-    let auth_service = AuthService::new();
-    auth_service.login(&pool, login_data.into_inner()).await
+) -> Result<HttpResponse, AppError> {
+    let response = AuthService::login(&state, login_data.into_inner(), &client_info(&req, &state.settings)).await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn create_user(
-    pool: web::Data<MySqlPool>,
-    user_data: web::Json<CreateUserRequest>,
-) -> Result<HttpResponse> {
-    let auth_service = AuthService::new();
-    auth_service.create_user(&pool, user_data.into_inner()).await
+// 两步登录：提交 login 返回的 challenge_token 和验证码
+pub async fn login_two_factor(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<TwoFactorLoginRequest>,
+) -> Result<HttpResponse, AppError> {
+    let response = AuthService::login_two_factor(&state, request.into_inner(), &client_info(&req, &state.settings)).await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn get_profile(req: HttpRequest) -> Result<HttpResponse> {
-    if let Some(claims) = req.extensions().get::<Claims>() {
-        Ok(HttpResponse::Ok().json(serde_json::json!({
-            "id": claims.sub,
-            "role": claims.role
-        })))
-    } else {
-        Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": "Authentication required"
-        })))
+// 钱包登录的挑战消息，钱包对 message 执行 signPersonalMessage
+pub async fn wallet_challenge(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<WalletLoginChallengeQuery>,
+) -> Result<HttpResponse, AppError> {
+    if !state.settings.auth.wallet_login {
+        return Err(AppError::Forbidden("Wallet login is disabled".to_string()));
     }
+    let client_ip = client_ip(&req, &state.settings);
+    let challenge = WalletService::create_login_challenge(&state.pool, &query.address, client_ip.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(challenge))
+}
+
+pub async fn wallet_login(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<WalletLoginRequest>,
+) -> Result<HttpResponse, AppError> {
+    let response = AuthService::login_with_wallet(&state, request.into_inner(), &client_info(&req, &state.settings)).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn refresh(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<RefreshTokenRequest>,
+) -> Result<HttpResponse, AppError> {
+    let response = AuthService::refresh(&state, request.into_inner(), &client_info(&req, &state.settings)).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn logout(
+    state: web::Data<AppState>,
+    request: web::Json<RefreshTokenRequest>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::logout(&state, request.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Logged out successfully"
+    })))
+}
+
+// 公开的 JWK Set，其他服务据此验证本服务签发的 access token（HS256 时为空）
+pub async fn jwks(state: web::Data<AppState>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+        .json(state.jwt.jwks())
+}
+
+pub async fn register(
+    state: web::Data<AppState>,
+    request: web::Json<RegisterRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = AuthService::register(&state, request.into_inner()).await?;
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "message": "Registration successful, please check your email to verify your account",
+        "user_id": user_id
+    })))
+}
+
+pub async fn verify_email(
+    state: web::Data<AppState>,
+    query: web::Query<VerifyEmailQuery>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::verify_email(&state, &query.token).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Email verified successfully, you can now log in"
+    })))
+}
+
+pub async fn resend_verification(
+    state: web::Data<AppState>,
+    request: web::Json<ResendVerificationRequest>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::resend_verification(&state, &request.email).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "If the address belongs to an unverified account, a new verification email has been sent"
+    })))
+}
+
+// 修改密码后其他会话全部失效，返回当前会话的新令牌对
+pub async fn change_password(
+    req: HttpRequest,
+    claims: Claims,
+    state: web::Data<AppState>,
+    request: web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse, AppError> {
+    let client_info = client_info(&req, &state.settings);
+    let response = AuthService::change_password(&state, claims.user_id()?, request.into_inner(), &client_info).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn forgot_password(
+    state: web::Data<AppState>,
+    request: web::Json<ForgotPasswordRequest>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::forgot_password(&state, &request.email).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "If the address belongs to an account, a password reset email has been sent"
+    })))
+}
+
+pub async fn reset_password(
+    state: web::Data<AppState>,
+    request: web::Json<ResetPasswordRequest>,
+) -> Result<HttpResponse, AppError> {
+    AuthService::reset_password(&state, request.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Password reset successfully, please log in again"
+    })))
+}
+
+pub async fn create_user(
+    state: web::Data<AppState>,
+    user_data: web::Json<CreateUserRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = AuthService::create_user(&state, user_data.into_inner()).await?;
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "message": "User created successfully",
+        "user_id": user_id
+    })))
 }
 
 // 获取所有角色（用于创建用户时选择）
-pub async fn get_roles(pool: web::Data<MySqlPool>) -> Result<HttpResponse> {
-    let roles = sqlx::query_as::<_, crate::models::user::SysRole>(
-        "SELECT id, role_key, role_name, status, created_at FROM sys_role WHERE status = 1"
-    )
-    .fetch_all(pool.as_ref())
-    .await;
-
-    match roles {
-        Ok(roles) => Ok(HttpResponse::Ok().json(roles)),
-        Err(_) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": "Failed to fetch roles"
-        }))),
-    }
+pub async fn get_roles(state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let roles = PermissionService::list_roles(&state.pool, false).await?;
+    Ok(HttpResponse::Ok().json(roles))
 }
 
 // 获取所有权限（用于管理界面）
-pub async fn get_permissions(pool: web::Data<MySqlPool>) -> Result<HttpResponse> {
-    let permissions = sqlx::query_as::<_, crate::models::user::SysPermission>(
-        "SELECT id, perm_key, perm_name FROM sys_permission"
-    )
-    .fetch_all(pool.as_ref())
-    .await;
-
-    match permissions {
-        Ok(permissions) => Ok(HttpResponse::Ok().json(permissions)),
-        Err(_) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": "Failed to fetch permissions"
-        }))),
+pub async fn get_permissions(state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let permissions = PermissionService::list_permissions(&state.pool).await?;
+    Ok(HttpResponse::Ok().json(permissions))
+}
+
+// 管理员解锁因登录失败被锁定的账号
+pub async fn unlock_user(
+    path: web::Path<i64>,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    LoginThrottleService::unlock_user(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "User unlocked successfully"
+    })))
+}
+
+// 管理员为丢失验证器的用户重置两步验证
+pub async fn reset_user_two_factor(
+    path: web::Path<i64>,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    TwoFactorService::reset(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Two-factor authentication reset successfully"
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App};
+    use crate::routes::auth_routes::configure_auth_routes;
+    use crate::test_support::{lazy_pool, test_state};
+    use crate::utils::jwt::JwtService;
+    use crate::utils::jwt_keys::JwtKeyRing;
+    use super::*;
+
+    async fn get_jwks(state: AppState) -> actix_web::dev::ServiceResponse {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .configure(configure_auth_routes),
+        )
+        .await;
+
+        let request = test::TestRequest::get().uri("/.well-known/jwks.json").to_request();
+        test::call_service(&app, request).await
     }
-}
\ No newline at end of file
+
+    #[actix_web::test]
+    async fn jwks_lists_public_keys_of_the_key_ring() {
+        let mut state = test_state(lazy_pool());
+        let key_ring = JwtKeyRing::load_dir("tests/fixtures/jwt/rotated", "2025-09").unwrap();
+        state.jwt = JwtService::new(key_ring, 15);
+
+        let response = get_jwks(state).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::CACHE_CONTROL).unwrap(), "public, max-age=300");
+
+        let body: serde_json::Value = test::read_body_json(response).await;
+
+        let keys = body["keys"].as_array().unwrap();
+        let kids: Vec<&str> = keys.iter().map(|key| key["kid"].as_str().unwrap()).collect();
+        assert_eq!(kids, ["2025-08", "2025-09"]);
+        assert!(keys.iter().all(|key| key.get("d").is_none()));
+    }
+
+    #[actix_web::test]
+    async fn jwks_is_empty_for_hs256() {
+        let response = get_jwks(test_state(lazy_pool())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body, serde_json::json!({ "keys": [] }));
+    }
+}
@@ -0,0 +1,78 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::error::AppError;
+use crate::models::identity::{OAuthAuthorizeResponse, OAuthCallbackRequest};
+use crate::oauth::github::GITHUB_PROVIDER;
+use crate::controllers::auth_controller::client_info;
+use crate::services::auth_service::AuthService;
+use crate::services::identity_service::{IdentityService, OAuthStateCheck};
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+// 获取 GitHub 授权地址，前端跳转后由回调页面提交 code 和 state
+pub async fn github_authorize(
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let github = state.github_client()?;
+    let oauth_state = IdentityService::create_state(&state.pool, GITHUB_PROVIDER, None).await?;
+    Ok(HttpResponse::Ok().json(OAuthAuthorizeResponse {
+        authorize_url: github.authorize_url(&oauth_state)?,
+    }))
+}
+
+// 返回与密码登录相同的结果（令牌对或两步验证挑战）
+pub async fn github_callback(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<OAuthCallbackRequest>
+) -> Result<HttpResponse, AppError> {
+    let response = AuthService::login_with_github(&state, request.into_inner(), &client_info(&req, &state.settings)).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_identities(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let identities = IdentityService::list_identities(&state.pool, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(identities))
+}
+
+// 为当前用户绑定 GitHub 账号的授权地址
+pub async fn github_link_authorize(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let github = state.github_client()?;
+    let oauth_state = IdentityService::create_state(&state.pool, GITHUB_PROVIDER, Some(claims.user_id()?)).await?;
+    Ok(HttpResponse::Ok().json(OAuthAuthorizeResponse {
+        authorize_url: github.authorize_url(&oauth_state)?,
+    }))
+}
+
+pub async fn link_github(
+    claims: Claims,
+    state: web::Data<AppState>,
+    request: web::Json<OAuthCallbackRequest>
+) -> Result<HttpResponse, AppError> {
+    let github = state.github_client()?;
+    let user_id = claims.user_id()?;
+
+    // state 必须由当前用户发起，防止把他人的 GitHub 账号绑定到自己名下
+    match IdentityService::consume_state(&state.pool, GITHUB_PROVIDER, &request.state).await? {
+        OAuthStateCheck::Link { user_id: owner } if owner == user_id => {}
+        _ => return Err(AppError::validation("Invalid or expired OAuth state")),
+    }
+
+    let profile = github.authenticate(&request.code).await?;
+    let identity = IdentityService::link(&state.pool, user_id, GITHUB_PROVIDER, &profile).await?;
+    Ok(HttpResponse::Created().json(identity))
+}
+
+pub async fn unlink_identity(
+    claims: Claims,
+    path: web::Path<String>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    IdentityService::unlink(&state.pool, claims.user_id()?, &path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("Identity unlinked successfully"))
+}
@@ -1,3 +1,12 @@
 pub mod task_controller;
 pub mod task_submission_controller;
-pub mod auth_controller;
\ No newline at end of file
+pub mod auth_controller;
+pub mod payout_controller;
+pub mod wallet_controller;
+pub mod two_factor_controller;
+pub mod identity_controller;
+pub mod user_controller;
+pub mod role_controller;
+pub mod profile_controller;
+pub mod personal_token_controller;
+pub mod session_controller;
\ No newline at end of file
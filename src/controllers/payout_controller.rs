@@ -0,0 +1,62 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::payout::{CreatePayoutRequest, PayoutQuery, PayoutStatus};
+use crate::services::payout_service::PayoutService;
+use crate::state::AppState;
+
+pub async fn get_payouts(
+    query: web::Query<PayoutQuery>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let response = PayoutService::list_payouts(&state.pool, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_payout_by_id(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let payout = PayoutService::get_payout(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(payout))
+}
+
+// 为已通过的提交创建（或补全收款地址）发放记录，并立即处理
+pub async fn create_submission_payout(
+    path: web::Path<i64>,
+    request: web::Json<CreatePayoutRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    // 未启用发放时不创建记录
+    state.payout_client()?;
+    let payout = PayoutService::create_for_submission(
+        &state,
+        path.into_inner(),
+        request.into_inner().recipient_address,
+    )
+    .await?
+    .ok_or_else(|| AppError::validation("Task has no reward to pay out"))?;
+
+    let payout = if payout.status == PayoutStatus::Pending {
+        PayoutService::process_payout(&state, payout.id).await?
+    } else {
+        payout
+    };
+    Ok(HttpResponse::Ok().json(payout))
+}
+
+// 重试失败（或尚未处理）的发放
+pub async fn retry_payout(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let payout = PayoutService::process_payout(&state, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(payout))
+}
+
+pub async fn sync_payout(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let payout = PayoutService::sync_payout(&state, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(payout))
+}
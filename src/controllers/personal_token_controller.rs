@@ -0,0 +1,36 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::personal_token::CreatePersonalTokenRequest;
+use crate::services::personal_token_service::PersonalTokenService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+pub async fn get_tokens(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let tokens = PersonalTokenService::list_tokens(&state.pool, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+pub async fn create_token(
+    claims: Claims,
+    request: web::Json<CreatePersonalTokenRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    // 尚未按策略启用两步验证的会话不能借令牌绕过限制
+    if claims.two_factor_pending {
+        return Err(AppError::Forbidden("Enable two-factor authentication first".to_string()));
+    }
+    let response = PersonalTokenService::create_token(&state.pool, claims.user_id()?, request.into_inner()).await?;
+    Ok(HttpResponse::Created().json(response))
+}
+
+pub async fn revoke_token(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    PersonalTokenService::revoke_token(&state.pool, claims.user_id()?, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("Personal access token revoked"))
+}
@@ -0,0 +1,25 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::user::UpdateProfileRequest;
+use crate::services::auth_service::AuthService;
+use crate::services::user_service::UserService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+// 当前用户资料，角色和权限以数据库为准而非令牌中的声明
+pub async fn get_profile(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let profile = UserService::get_profile(&state.pool, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+pub async fn update_profile(
+    claims: Claims,
+    request: web::Json<UpdateProfileRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let profile = AuthService::update_profile(&state, claims.user_id()?, request.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
@@ -0,0 +1,90 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::user::{
+    CreatePermissionRequest, CreateRoleRequest, SetRolePermissionsRequest, UpdatePermissionRequest, UpdateRoleRequest,
+};
+use crate::services::permission_service::PermissionService;
+use crate::state::AppState;
+
+// 包含已禁用的角色
+pub async fn get_roles(state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let roles = PermissionService::list_roles(&state.pool, true).await?;
+    Ok(HttpResponse::Ok().json(roles))
+}
+
+pub async fn get_role_by_id(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let role = PermissionService::get_role(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(role))
+}
+
+pub async fn create_role(
+    request: web::Json<CreateRoleRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let role = PermissionService::create_role(&state.pool, request.into_inner()).await?;
+    Ok(HttpResponse::Created().json(role))
+}
+
+pub async fn update_role(
+    path: web::Path<i64>,
+    request: web::Json<UpdateRoleRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let role = PermissionService::update_role(&state.pool, path.into_inner(), request.into_inner()).await?;
+    state.permission_cache.invalidate_all();
+    Ok(HttpResponse::Ok().json(role))
+}
+
+pub async fn delete_role(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    PermissionService::delete_role(&state.pool, path.into_inner()).await?;
+    state.permission_cache.invalidate_all();
+    Ok(HttpResponse::Ok().json("Role deleted successfully"))
+}
+
+// 整体替换角色权限，持有该角色的用户立即生效
+pub async fn set_role_permissions(
+    path: web::Path<i64>,
+    request: web::Json<SetRolePermissionsRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let role = PermissionService::set_role_permissions(&state.pool, path.into_inner(), &request.permission_ids).await?;
+    state.permission_cache.invalidate_all();
+    Ok(HttpResponse::Ok().json(role))
+}
+
+pub async fn get_permissions(state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let permissions = PermissionService::list_permissions(&state.pool).await?;
+    Ok(HttpResponse::Ok().json(permissions))
+}
+
+pub async fn create_permission(
+    request: web::Json<CreatePermissionRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let permission = PermissionService::create_permission(&state.pool, request.into_inner()).await?;
+    Ok(HttpResponse::Created().json(permission))
+}
+
+pub async fn update_permission(
+    path: web::Path<i64>,
+    request: web::Json<UpdatePermissionRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let permission = PermissionService::update_permission(&state.pool, path.into_inner(), request.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(permission))
+}
+
+pub async fn delete_permission(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    PermissionService::delete_permission(&state.pool, path.into_inner()).await?;
+    state.permission_cache.invalidate_all();
+    Ok(HttpResponse::Ok().json("Permission deleted successfully"))
+}
@@ -0,0 +1,41 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::services::session_service::SessionService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+pub async fn get_sessions(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let sessions = SessionService::list_sessions(&state.pool, claims.user_id()?, &claims.sid).await?;
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+// 可以登出当前会话，效果与 logout 相同
+pub async fn revoke_session(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    SessionService::revoke_session(&state, claims.user_id()?, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("Session revoked"))
+}
+
+pub async fn get_user_sessions(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let sessions = SessionService::list_sessions(&state.pool, path.into_inner(), &claims.sid).await?;
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+// 管理员强制登出用户的所有会话
+pub async fn revoke_user_sessions(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    SessionService::revoke_all(&state, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("All sessions of the user have been revoked"))
+}
@@ -1,83 +1,109 @@
-use actix_web::{web, HttpResponse, Result};
-use sqlx::MySqlPool;
-use crate::models::task::{CreateTaskRequest, UpdateTaskRequest, TaskQuery};
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::middleware::permission::UserPermissions;
+use crate::models::task::{CreateTaskRequest, UpdateTaskRequest, TaskQuery, TaskStatus, SetTaskDeadlineRequest};
 use crate::services::task_service::TaskService;
+use crate::state::AppState;
+use crate::utils::jwt::Claims;
+
+// 可以编辑任务的用户同时可以看到草稿和已关闭的任务
+fn can_manage_tasks(permissions: &UserPermissions) -> bool {
+    permissions.has("task:update")
+}
 
 pub async fn get_tasks(
     query: web::Query<TaskQuery>,
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
-    match TaskService::get_tasks_with_pagination(pool.get_ref(), query.into_inner()).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(response)),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+    permissions: UserPermissions,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let response = TaskService::get_tasks_with_pagination(
+        &state.pool,
+        query.into_inner(),
+        can_manage_tasks(&permissions),
+    ).await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn get_all_tasks(pool: web::Data<MySqlPool>) -> Result<HttpResponse> {
-    match TaskService::get_all_tasks(pool.get_ref()).await {
-        Ok(tasks) => Ok(HttpResponse::Ok().json(tasks)),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+pub async fn get_all_tasks(permissions: UserPermissions, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let tasks = TaskService::get_all_tasks(&state.pool, can_manage_tasks(&permissions)).await?;
+    Ok(HttpResponse::Ok().json(tasks))
 }
 
-pub async fn get_task_by_id(path: web::Path<i64>, pool: web::Data<MySqlPool>) -> Result<HttpResponse> {
-    let id = path.into_inner();
-    
-    match TaskService::get_task_by_id(pool.get_ref(), id).await {
-        Ok(Some(task)) => Ok(HttpResponse::Ok().json(task)),
-        Ok(None) => Ok(HttpResponse::NotFound().json("Task not found")),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+// 同时返回当前用户的个人截止时间
+pub async fn get_task_by_id(
+    path: web::Path<i64>,
+    claims: Claims,
+    permissions: UserPermissions,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let task_id = path.into_inner();
+    let mut task = TaskService::get_task_by_id(&state.pool, task_id, can_manage_tasks(&permissions)).await?;
+    task.due_at = TaskService::get_deadline(&state.pool, task_id, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(task))
 }
 
 pub async fn create_task(
     task: web::Json<CreateTaskRequest>,
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
-    match TaskService::create_task(pool.get_ref(), task.into_inner()).await {
-        Ok(id) => Ok(HttpResponse::Created().json(serde_json::json!({"id": id}))),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let id = TaskService::create_task(&state.pool, task.into_inner()).await?;
+    Ok(HttpResponse::Created().json(serde_json::json!({"id": id})))
 }
 
 pub async fn update_task(
     path: web::Path<i64>,
     task: web::Json<UpdateTaskRequest>,
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
-    let id = path.into_inner();
-    
-    match TaskService::update_task(pool.get_ref(), id, task.into_inner()).await {
-        Ok(true) => Ok(HttpResponse::Ok().json("Task updated successfully")),
-        Ok(false) => Ok(HttpResponse::NotFound().json("Task not found or no changes made")),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    TaskService::update_task(&state.pool, path.into_inner(), task.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("Task updated successfully"))
+}
+
+pub async fn publish_task(path: web::Path<i64>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let task = TaskService::set_status(&state.pool, path.into_inner(), TaskStatus::Published).await?;
+    Ok(HttpResponse::Ok().json(task))
+}
+
+pub async fn close_task(path: web::Path<i64>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let task = TaskService::set_status(&state.pool, path.into_inner(), TaskStatus::Closed).await?;
+    Ok(HttpResponse::Ok().json(task))
+}
+
+// 归档的任务恢复为草稿，需重新发布
+pub async fn restore_task(path: web::Path<i64>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let task = TaskService::set_status(&state.pool, path.into_inner(), TaskStatus::Draft).await?;
+    Ok(HttpResponse::Ok().json(task))
+}
+
+// 删除任务只做归档，提交和发放记录保留
+pub async fn delete_task(path: web::Path<i64>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    TaskService::set_status(&state.pool, path.into_inner(), TaskStatus::Archived).await?;
+    Ok(HttpResponse::Ok().json("Task archived successfully"))
+}
+
+// 管理员彻底删除已归档的任务及其提交
+pub async fn purge_task(path: web::Path<i64>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    TaskService::purge_task(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("Task purged successfully"))
+}
+
+pub async fn get_deadlines(path: web::Path<i64>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let deadlines = TaskService::list_deadlines(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(deadlines))
+}
+
+pub async fn set_deadline(
+    path: web::Path<(i64, i64)>,
+    request: web::Json<SetTaskDeadlineRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let (task_id, user_id) = path.into_inner();
+    let deadline = TaskService::set_deadline(&state.pool, task_id, user_id, request.due_at).await?;
+    Ok(HttpResponse::Ok().json(deadline))
 }
 
-pub async fn delete_task(path: web::Path<i64>, pool: web::Data<MySqlPool>) -> Result<HttpResponse> {
-    let id = path.into_inner();
-    
-    match TaskService::delete_task(pool.get_ref(), id).await {
-        Ok(true) => Ok(HttpResponse::Ok().json("Task deleted successfully")),
-        Ok(false) => Ok(HttpResponse::NotFound().json("Task not found")),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+pub async fn delete_deadline(path: web::Path<(i64, i64)>, state: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let (task_id, user_id) = path.into_inner();
+    TaskService::delete_deadline(&state.pool, task_id, user_id).await?;
+    Ok(HttpResponse::Ok().json("Deadline removed successfully"))
 }
\ No newline at end of file
@@ -1,84 +1,241 @@
-use actix_web::{web, HttpResponse, Result};
-use sqlx::MySqlPool;
-use crate::models::task_submission::{TaskSubmissionQuery, RejectSubmissionRequest};
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::middleware::permission::UserPermissions;
+use crate::models::payout::PayoutStatus;
+use crate::models::task_submission::{
+    TaskSubmissionQuery, RejectSubmissionRequest, CreateTaskSubmissionRequest, UpdateTaskSubmissionRequest,
+    ApproveSubmissionRequest, RequestChangesRequest,
+};
+use crate::services::payout_service::PayoutService;
 use crate::services::task_submission_service::TaskSubmissionService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+// 审核人员可以查看所有提交，其他用户只能查看自己的
+fn owner_filter(claims: &Claims, permissions: &UserPermissions) -> Result<Option<i64>, AppError> {
+    if permissions.has("submission:review") {
+        Ok(None)
+    } else {
+        claims.user_id().map(Some)
+    }
+}
 
 pub async fn get_submissions_by_task_id(
+    claims: Claims,
+    permissions: UserPermissions,
     path: web::Path<i64>,
     query: web::Query<TaskSubmissionQuery>,
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
-    let task_id = path.into_inner();
-    
-    match TaskSubmissionService::get_submissions_by_task_id(
-        pool.get_ref(), 
-        task_id, 
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let response = TaskSubmissionService::get_submissions_by_task_id(
+        &state.pool, 
+        path.into_inner(), 
+        owner_filter(&claims, &permissions)?,
         query.into_inner()
-    ).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(response)),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+    ).await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
 pub async fn get_submission_by_id(
+    claims: Claims,
+    permissions: UserPermissions,
     path: web::Path<i64>, 
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
-    let id = path.into_inner();
-    
-    match TaskSubmissionService::get_submission_by_id(pool.get_ref(), id).await {
-        Ok(Some(submission)) => Ok(HttpResponse::Ok().json(submission)),
-        Ok(None) => Ok(HttpResponse::NotFound().json("Task submission not found")),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
-        }
-    }
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let submission = TaskSubmissionService::get_submission_by_id(
+        &state.pool,
+        path.into_inner(),
+        owner_filter(&claims, &permissions)?
+    ).await?;
+    Ok(HttpResponse::Ok().json(submission))
+}
+
+pub async fn get_submission_history(
+    claims: Claims,
+    permissions: UserPermissions,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let history = TaskSubmissionService::get_submission_history(
+        &state.pool,
+        path.into_inner(),
+        owner_filter(&claims, &permissions)?
+    ).await?;
+    Ok(HttpResponse::Ok().json(history))
 }
 
+pub async fn start_review(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    TaskSubmissionService::start_review(&state.pool, path.into_inner(), claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Submission is now in review",
+        "status": "in_review"
+    })))
+}
+
+// 审核通过；启用链上发放时同时创建发放记录并在后台转账
 pub async fn approve_submission(
+    claims: Claims,
     path: web::Path<i64>,
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
+    request: Option<web::Json<ApproveSubmissionRequest>>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
     let submission_id = path.into_inner();
-    
-    match TaskSubmissionService::approve_submission(pool.get_ref(), submission_id).await {
-        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
-            "message": "Submission approved successfully",
-            "status": "approved"
-        }))),
-        Ok(false) => Ok(HttpResponse::NotFound().json("Submission not found")),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
+    let request = request.map(|r| r.into_inner()).unwrap_or_default();
+
+    let payout = if state.payout_client.is_some() {
+        let payout = PayoutService::approve_with_payout(
+            &state,
+            submission_id,
+            claims.user_id()?,
+            request.comment,
+            request.recipient_address
+        ).await?;
+        if let Some(payout) = &payout
+            && payout.status == PayoutStatus::Pending
+        {
+            PayoutService::spawn_process(state.clone().into_inner(), payout.id);
         }
-    }
+        payout
+    } else {
+        TaskSubmissionService::approve_submission(&state.pool, submission_id, claims.user_id()?, request.comment).await?;
+        None
+    };
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Submission approved successfully",
+        "status": "approved",
+        "payout": payout
+    })))
 }
 
 pub async fn reject_submission(
+    claims: Claims,
     path: web::Path<i64>,
     request: web::Json<RejectSubmissionRequest>,
-    pool: web::Data<MySqlPool>
-) -> Result<HttpResponse> {
-    let submission_id = path.into_inner();
-    let reject_request = request.into_inner();
-    
-    match TaskSubmissionService::reject_submission(
-        pool.get_ref(), 
-        submission_id, 
-        reject_request.note
-    ).await {
-        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
-            "message": "Submission rejected successfully",
-            "status": "rejected"
-        }))),
-        Ok(false) => Ok(HttpResponse::NotFound().json("Submission not found")),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            Ok(HttpResponse::InternalServerError().json("Database error"))
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    TaskSubmissionService::reject_submission(
+        &state.pool,
+        path.into_inner(),
+        claims.user_id()?,
+        request.into_inner().note
+    ).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Submission rejected successfully",
+        "status": "rejected"
+    })))
+}
+
+pub async fn request_changes(
+    claims: Claims,
+    path: web::Path<i64>,
+    request: web::Json<RequestChangesRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    TaskSubmissionService::request_changes(
+        &state.pool,
+        path.into_inner(),
+        claims.user_id()?,
+        request.into_inner().note
+    ).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Changes requested",
+        "status": "changes_requested"
+    })))
+}
+
+pub async fn withdraw_submission(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    TaskSubmissionService::withdraw_submission(&state.pool, path.into_inner(), claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Submission withdrawn",
+        "status": "withdrawn"
+    })))
+}
+
+pub async fn create_submission(
+    claims: Claims,
+    request: web::Json<CreateTaskSubmissionRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let id = TaskSubmissionService::create_submission(&state.pool, claims.user_id()?, request.into_inner()).await?;
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "id": id,
+        "status": "pending"
+    })))
+}
+
+pub async fn update_submission(
+    claims: Claims,
+    path: web::Path<i64>,
+    request: web::Json<UpdateTaskSubmissionRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    TaskSubmissionService::update_submission(
+        &state.pool,
+        path.into_inner(),
+        claims.user_id()?,
+        request.into_inner()
+    ).await?;
+    Ok(HttpResponse::Ok().json("Submission updated successfully"))
+}
+
+// 需要 MySQL：L2E_TEST_DATABASE_URL=... cargo test -- --ignored
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App};
+    use actix_web_httpauth::middleware::HttpAuthentication;
+    use super::*;
+    use crate::routes::task_submission_routes::configure_task_submission_routes;
+    use crate::test_support::{database_pool, grant_permission, insert_submission, insert_user, test_state};
+    use crate::utils::jwt::jwt_validator;
+
+    fn token_for(state: &AppState, user_id: i64) -> String {
+        let jti = uuid::Uuid::new_v4().to_string();
+        state.jwt.create_token(&user_id.to_string(), &[], &[], &jti, "", false).unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn learner_cannot_read_another_learners_submission() {
+        let state = web::Data::new(test_state(database_pool().await));
+        let owner = insert_user(&state.pool).await;
+        let learner = insert_user(&state.pool).await;
+        let reviewer = insert_user(&state.pool).await;
+        grant_permission(&state.pool, reviewer, "submission:review").await;
+        let submission_id = insert_submission(&state.pool, owner, "pending").await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .service(
+                    web::scope("/api")
+                        .wrap(HttpAuthentication::bearer(jwt_validator))
+                        .configure(configure_task_submission_routes),
+                ),
+        )
+        .await;
+
+        for uri in [format!("/api/submissions/{}", submission_id), format!("/api/submissions/{}/history", submission_id)] {
+            for (user_id, expected) in [
+                (learner, StatusCode::FORBIDDEN),
+                (owner, StatusCode::OK),
+                (reviewer, StatusCode::OK),
+            ] {
+                let request = test::TestRequest::get()
+                    .uri(&uri)
+                    .insert_header(("Authorization", format!("Bearer {}", token_for(&state, user_id))))
+                    .to_request();
+                let response = test::call_service(&app, request).await;
+                assert_eq!(response.status(), expected, "GET {} as user {}", uri, user_id);
+            }
         }
     }
-}
\ No newline at end of file
+}
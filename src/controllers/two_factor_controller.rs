@@ -0,0 +1,43 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::two_factor::{RecoveryCodesResponse, TwoFactorCodeRequest};
+use crate::services::two_factor_service::TwoFactorService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+pub async fn get_two_factor_status(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let status = TwoFactorService::status(&state.pool, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(status))
+}
+
+pub async fn setup_two_factor(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let response = TwoFactorService::setup(&state, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+// 启用后需刷新令牌才能获得完整权限（auth.two_factor.require_for_reviewers 开启时）
+pub async fn verify_two_factor(
+    claims: Claims,
+    state: web::Data<AppState>,
+    request: web::Json<TwoFactorCodeRequest>
+) -> Result<HttpResponse, AppError> {
+    let recovery_codes = TwoFactorService::enable(&state.pool, claims.user_id()?, &request.code).await?;
+    Ok(HttpResponse::Ok().json(RecoveryCodesResponse { recovery_codes }))
+}
+
+pub async fn disable_two_factor(
+    claims: Claims,
+    state: web::Data<AppState>,
+    request: web::Json<TwoFactorCodeRequest>
+) -> Result<HttpResponse, AppError> {
+    TwoFactorService::disable(&state.pool, claims.user_id()?, &request.code).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Two-factor authentication disabled"
+    })))
+}
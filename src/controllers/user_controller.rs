@@ -0,0 +1,74 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::user::{SetUserRolesRequest, UpdateUserRequest, UserQuery};
+use crate::services::user_service::UserService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+pub async fn get_users(
+    query: web::Query<UserQuery>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let response = UserService::list_users(&state.pool, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_user_by_id(
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let user = UserService::get_user(&state.pool, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+pub async fn update_user(
+    path: web::Path<i64>,
+    request: web::Json<UpdateUserRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let user = UserService::update_user(&state.pool, path.into_inner(), request.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+pub async fn delete_user(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let user_id = path.into_inner();
+    UserService::delete_user(&state, user_id, claims.user_id()?).await?;
+    state.permission_cache.invalidate_user(user_id);
+    Ok(HttpResponse::Ok().json("User deleted successfully"))
+}
+
+// 整体替换用户角色，权限立即生效，令牌中的角色在下次登录或刷新令牌后更新
+pub async fn set_user_roles(
+    path: web::Path<i64>,
+    request: web::Json<SetUserRolesRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let user_id = path.into_inner();
+    let user = UserService::set_roles(&state.pool, user_id, &request.role_ids).await?;
+    state.permission_cache.invalidate_user(user_id);
+    Ok(HttpResponse::Ok().json(user))
+}
+
+pub async fn disable_user(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let user_id = path.into_inner();
+    let user = UserService::set_enabled(&state, user_id, claims.user_id()?, false).await?;
+    state.permission_cache.invalidate_user(user_id);
+    Ok(HttpResponse::Ok().json(user))
+}
+
+pub async fn enable_user(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let user = UserService::set_enabled(&state, path.into_inner(), claims.user_id()?, true).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
@@ -0,0 +1,65 @@
+use actix_web::{web, HttpResponse};
+use crate::error::AppError;
+use crate::models::wallet::{BindWalletRequest, UserWallet};
+use crate::services::payout_service::PayoutService;
+use crate::services::wallet_service::WalletService;
+use crate::utils::jwt::Claims;
+use crate::state::AppState;
+
+// 默认地址变更后，处理该学员等待收款地址的发放
+async fn release_awaiting_payouts(state: &web::Data<AppState>, wallet: &UserWallet) -> Result<(), AppError> {
+    if state.payout_client.is_none() || !wallet.is_primary {
+        return Ok(());
+    }
+
+    let payout_ids = PayoutService::assign_awaiting_address(&state.pool, wallet.user_id, &wallet.address).await?;
+    for payout_id in payout_ids {
+        PayoutService::spawn_process(state.clone().into_inner(), payout_id);
+    }
+    Ok(())
+}
+
+pub async fn get_wallets(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let wallets = WalletService::list_wallets(&state.pool, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(wallets))
+}
+
+pub async fn create_challenge(
+    claims: Claims,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let challenge = WalletService::create_challenge(&state.pool, claims.user_id()?).await?;
+    Ok(HttpResponse::Ok().json(challenge))
+}
+
+pub async fn bind_wallet(
+    claims: Claims,
+    request: web::Json<BindWalletRequest>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let wallet = WalletService::bind_wallet(&state.pool, claims.user_id()?, request.into_inner()).await?;
+    release_awaiting_payouts(&state, &wallet).await?;
+    Ok(HttpResponse::Created().json(wallet))
+}
+
+pub async fn set_primary_wallet(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    let wallet = WalletService::set_primary(&state.pool, claims.user_id()?, path.into_inner()).await?;
+    release_awaiting_payouts(&state, &wallet).await?;
+    Ok(HttpResponse::Ok().json(wallet))
+}
+
+pub async fn delete_wallet(
+    claims: Claims,
+    path: web::Path<i64>,
+    state: web::Data<AppState>
+) -> Result<HttpResponse, AppError> {
+    WalletService::delete_wallet(&state.pool, claims.user_id()?, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json("Wallet removed successfully"))
+}
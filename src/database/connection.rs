@@ -1,13 +1,28 @@
 use sqlx::{MySqlPool, mysql::MySqlPoolOptions};
 use crate::config::settings::DatabaseSettings;
+use crate::database::migration::{run_migrations, run_seed};
 
-pub async fn create_pool() -> MySqlPool {
-    let db_settings = DatabaseSettings::from_env();
-    
-    MySqlPoolOptions::new()
+pub async fn create_pool(db_settings: &DatabaseSettings) -> MySqlPool {
+    let pool = MySqlPoolOptions::new()
         .max_connections(db_settings.max_connections)
         .min_connections(db_settings.min_connections)
         .connect(&db_settings.url)
         .await
-        .expect("Failed to connect to MySQL")
-}
\ No newline at end of file
+        .expect("Failed to connect to MySQL");
+
+    if db_settings.run_migrations {
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run database migrations");
+        println!("Database migrations applied");
+    }
+
+    if db_settings.seed {
+        run_seed(&pool)
+            .await
+            .expect("Failed to seed database");
+        println!("Database seed data applied");
+    }
+
+    pool
+}
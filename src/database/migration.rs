@@ -0,0 +1,17 @@
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::{Executor, MySqlPool};
+
+// 表结构迁移在编译期嵌入二进制，版本记录在 _sqlx_migrations 表中
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+// 初始数据与表结构分离，语句均为幂等的 INSERT IGNORE
+const SEED_SQL: &str = include_str!("../../sql/seed.sql");
+
+pub async fn run_migrations(pool: &MySqlPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+pub async fn run_seed(pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    pool.execute(SEED_SQL).await?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use actix_web::http::{header, StatusCode};
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt;
+
+// 统一的应用错误，所有 service 返回 Result<_, AppError>，handler 直接使用 `?`
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    Validation {
+        message: String,
+        details: Option<serde_json::Value>,
+    },
+    Conflict(String),
+    Forbidden(String),
+    Unauthorized(String),
+    // retry_after 为秒数，同时写入 Retry-After 响应头
+    TooManyRequests {
+        message: String,
+        retry_after: u64,
+    },
+    Database(sqlx::Error),
+    Internal(String),
+}
+
+// 错误响应体：{code, message, details}
+#[derive(Serialize)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<serde_json::Value>,
+}
+
+impl AppError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation {
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn validation_with_details(message: impl Into<String>, details: serde_json::Value) -> Self {
+        AppError::Validation {
+            message: message.into(),
+            details: Some(details),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Validation { .. } => "VALIDATION_ERROR",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::TooManyRequests { .. } => "TOO_MANY_REQUESTS",
+            AppError::Database(_) => "DATABASE_ERROR",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::Forbidden(message)
+            | AppError::Unauthorized(message)
+            | AppError::Internal(message) => write!(f, "{}", message),
+            AppError::Validation { message, .. } | AppError::TooManyRequests { message, .. } => write!(f, "{}", message),
+            AppError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation { .. } => StatusCode::BAD_REQUEST,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        // 内部错误只记录日志，不把细节返回给客户端
+        let message = match self {
+            AppError::Database(e) => {
+                eprintln!("Database error: {}", e);
+                "Database error".to_string()
+            }
+            AppError::Internal(message) => {
+                eprintln!("Internal error: {}", message);
+                "Internal server error".to_string()
+            }
+            other => other.to_string(),
+        };
+
+        let details = match self {
+            AppError::Validation { details, .. } => details.clone(),
+            AppError::TooManyRequests { retry_after, .. } => Some(serde_json::json!({ "retry_after": retry_after })),
+            _ => None,
+        };
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let AppError::TooManyRequests { retry_after, .. } = self {
+            response.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        }
+
+        response.json(ErrorBody {
+            code: self.code(),
+            message,
+            details,
+        })
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        AppError::Database(e)
+    }
+}
+
+impl From<bcrypt::BcryptError> for AppError {
+    fn from(e: bcrypt::BcryptError) -> Self {
+        AppError::Internal(format!("Password hashing failed: {}", e))
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for AppError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        AppError::Internal(format!("Token encoding failed: {}", e))
+    }
+}
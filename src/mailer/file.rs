@@ -0,0 +1,48 @@
+use chrono::Utc;
+use futures_util::future::BoxFuture;
+use std::path::PathBuf;
+use super::{EmailMessage, Mailer, MailerError};
+
+// 每封邮件写成一个 .eml 文件，便于在本地查看验证链接
+pub struct FileMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(from: &str, dir: &str) -> Self {
+        FileMailer {
+            from: from.to_string(),
+            dir: PathBuf::from(dir),
+        }
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, message: EmailMessage) -> BoxFuture<'_, Result<(), MailerError>> {
+        Box::pin(async move {
+            let now = Utc::now();
+            let path = self.dir.join(format!(
+                "{}-{}.eml",
+                now.format("%Y%m%d%H%M%S"),
+                uuid::Uuid::new_v4()
+            ));
+            let content = format!(
+                "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+                self.from,
+                message.to,
+                message.subject,
+                now.to_rfc2822(),
+                message.body
+            );
+
+            tokio::fs::create_dir_all(&self.dir)
+                .await
+                .map_err(|e| MailerError(format!("{}: {}", self.dir.display(), e)))?;
+            tokio::fs::write(&path, content)
+                .await
+                .map_err(|e| MailerError(format!("{}: {}", path.display(), e)))?;
+            Ok(())
+        })
+    }
+}
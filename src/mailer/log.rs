@@ -0,0 +1,25 @@
+use futures_util::future::{ready, BoxFuture};
+use super::{EmailMessage, Mailer, MailerError};
+
+// 把邮件内容打印到标准输出，适合本地开发
+pub struct LogMailer {
+    from: String,
+}
+
+impl LogMailer {
+    pub fn new(from: &str) -> Self {
+        LogMailer {
+            from: from.to_string(),
+        }
+    }
+}
+
+impl Mailer for LogMailer {
+    fn send(&self, message: EmailMessage) -> BoxFuture<'_, Result<(), MailerError>> {
+        println!(
+            "[mail] from: {}\n[mail] to: {}\n[mail] subject: {}\n{}",
+            self.from, message.to, message.subject, message.body
+        );
+        Box::pin(ready(Ok(())))
+    }
+}
@@ -0,0 +1,46 @@
+pub mod file;
+pub mod log;
+
+use futures_util::future::BoxFuture;
+use std::fmt;
+use std::sync::Arc;
+use crate::config::settings::{MailBackend, MailSettings};
+use crate::error::AppError;
+use file::FileMailer;
+use log::LogMailer;
+
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String, // 纯文本
+}
+
+#[derive(Debug)]
+pub struct MailerError(pub String);
+
+impl fmt::Display for MailerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to send email: {}", self.0)
+    }
+}
+
+impl std::error::Error for MailerError {}
+
+impl From<MailerError> for AppError {
+    fn from(e: MailerError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+// 邮件发送接口：本地开发使用日志 / 文件实现，生产环境可接入 SMTP 或第三方服务
+pub trait Mailer: Send + Sync {
+    fn send(&self, message: EmailMessage) -> BoxFuture<'_, Result<(), MailerError>>;
+}
+
+pub fn from_settings(settings: &MailSettings) -> Arc<dyn Mailer> {
+    match settings.backend {
+        MailBackend::Log => Arc::new(LogMailer::new(&settings.from)),
+        MailBackend::File => Arc::new(FileMailer::new(&settings.from, &settings.file_dir)),
+    }
+}
@@ -1,3 +1,4 @@
+mod error;
 mod models;
 mod controllers;
 mod services;
@@ -6,18 +7,31 @@ mod database;
 mod config;
 mod utils;
 mod middleware;
+mod payout;
+mod mailer;
+mod oauth;
+mod state;
+#[cfg(test)]
+mod test_support;
 
 use actix_web::{web, App, HttpServer, middleware::Logger};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use actix_cors::Cors;
+use config::settings::Settings;
 use database::connection::create_pool;
 use routes::{
     task_routes::configure_task_routes, 
     task_submission_routes::configure_task_submission_routes,
-    auth_routes::{configure_auth_routes, configure_protected_auth_routes}
+    auth_routes::{configure_auth_routes, configure_protected_auth_routes},
+    payout_routes::configure_payout_routes,
+    me_routes::configure_me_routes
 };
+use error::AppError;
 use utils::jwt::jwt_validator;
 use services::auth_service::AuthService;
+use services::payout_service::PayoutService;
+use services::task_service::TaskService;
+use state::AppState;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -27,25 +41,61 @@ async fn main() -> std::io::Result<()> {
     // 初始化日志
     env_logger::init();
     
+    // 加载配置，配置有误时直接拒绝启动
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
     // 创建数据库连接池
-    let pool = create_pool().await;
-    
-    // 初始化默认用户密码
-    let auth_service = AuthService::new();
-    if let Err(e) = auth_service.init_default_users(&pool).await {
+    let pool = create_pool(&settings.database).await;
+    println!("Connected to MySQL database");
+
+    let bind_address = settings.bind_address();
+    let workers = settings.server.workers;
+
+    // 共享状态：连接池、配置、签名密钥、邮件、出款客户端和缓存只创建一次
+    let state = match AppState::from_settings(pool, settings) {
+        Ok(state) => web::Data::new(state),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(client) = &state.payout_client {
+        println!("Payouts enabled, sender address: {}", client.sender_address());
+    }
+
+    // 按需重置默认用户密码（仅用于本地开发）
+    if state.settings.auth.reset_default_passwords
+        && let Err(e) = AuthService::init_default_users(&state).await
+    {
         eprintln!("Failed to initialize default users: {}", e);
     }
-    
-    println!("Connected to MySQL database");
-    println!("Default users: admin/admin123, demo/user123");
-    println!("Server starting at http://127.0.0.1:8080");
-    
-    HttpServer::new(move || {
+
+    // 定时关闭到期的任务
+    TaskService::spawn_scheduler(state.clone().into_inner());
+
+    // 定时恢复处理中断的发放
+    if state.payout_client.is_some() {
+        PayoutService::spawn_scheduler(state.clone().into_inner());
+    }
+
+    println!("Server starting at http://{}:{}", state.settings.server.host, state.settings.server.port);
+
+    let mut server = HttpServer::new(move || {
         // 配置 CORS
-        let cors = Cors::default()
-            .allowed_origin("http://localhost:5173")
-            .allowed_origin("http://127.0.0.1:5173")
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+        let cors = state
+            .settings
+            .server
+            .cors_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+            .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"])
             .allowed_headers(vec!["Content-Type", "Authorization"])
             .max_age(3600);
         
@@ -55,7 +105,14 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(cors)
             .wrap(Logger::default())
-            .app_data(web::Data::new(pool.clone()))
+            .app_data(state.clone())
+            // 请求体/参数解析失败时也返回统一的错误结构
+            .app_data(web::JsonConfig::default()
+                .error_handler(|err, _| AppError::validation(err.to_string()).into()))
+            .app_data(web::QueryConfig::default()
+                .error_handler(|err, _| AppError::validation(err.to_string()).into()))
+            .app_data(web::PathConfig::default()
+                .error_handler(|err, _| AppError::validation(err.to_string()).into()))
             .configure(configure_auth_routes) // 公开的认证路由
             .service(
                 web::scope("/api")
@@ -63,10 +120,14 @@ async fn main() -> std::io::Result<()> {
                     .configure(configure_protected_auth_routes)
                     .configure(configure_task_routes)
                     .configure(configure_task_submission_routes)
-                    
+                    .configure(configure_payout_routes)
+                    .configure(configure_me_routes)
             )
-    })
-    .bind(("127.0.0.1", 8080))?
-    .run()
-    .await
+    });
+
+    if let Some(workers) = workers {
+        server = server.workers(workers);
+    }
+
+    server.bind(bind_address)?.run().await
 }
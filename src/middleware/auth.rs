@@ -1,25 +1,16 @@
-use actix_web::{dev::ServiceRequest, Error, HttpMessage, HttpResponse};
+use actix_web::{dev::ServiceRequest, Error, HttpMessage, ResponseError};
 use actix_web::body::{BoxBody, MessageBody};
 use actix_web::dev::{forward_ready, Service, ServiceResponse, Transform};
-use actix_web::http::StatusCode;
 use futures_util::future::{ok, Ready};
-use std::future::{ready, Future};
+use std::future::Future;
 use std::pin::Pin;
+use crate::error::AppError;
 use crate::utils::jwt::Claims;
 
-pub struct RequireRole {
-    required_role: String,
-}
+// 只接受登录会话：账号安全相关的接口不允许使用个人访问令牌
+pub struct RequireSession;
 
-impl RequireRole {
-    pub fn new(role: &str) -> Self {
-        RequireRole {
-            required_role: role.to_string(),
-        }
-    }
-}
-
-impl<S, B> Transform<S, ServiceRequest> for RequireRole
+impl<S, B> Transform<S, ServiceRequest> for RequireSession
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
     S::Future: 'static,
@@ -28,23 +19,19 @@ where
     type Response = ServiceResponse<BoxBody>;
     type Error = Error;
     type InitError = ();
-    type Transform = RequireRoleMiddleware<S>;
+    type Transform = RequireSessionMiddleware<S>;
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ok(RequireRoleMiddleware {
-            service,
-            required_role: self.required_role.clone(),
-        })
+        ok(RequireSessionMiddleware { service })
     }
 }
 
-pub struct RequireRoleMiddleware<S> {
+pub struct RequireSessionMiddleware<S> {
     service: S,
-    required_role: String,
 }
 
-impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+impl<S, B> Service<ServiceRequest> for RequireSessionMiddleware<S>
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
     S::Future: 'static,
@@ -57,40 +44,33 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let required_role = self.required_role.clone();
-
-        // 从请求扩展中获取用户声明
-        let user_role = req
+        let personal_token = req
             .extensions()
             .get::<Claims>()
-            .map(|c| c.role.clone());
+            .map(|c| c.personal_token_id.is_some());
 
-        match user_role {
-            Some(role) if role == required_role || role == "admin" => {
+        match personal_token {
+            Some(false) => {
                 let fut = self.service.call(req);
                 Box::pin(async move {
                     let res = fut.await?;
                     Ok(res.map_into_boxed_body())
                 })
             }
-            Some(_) => {
+            Some(true) => {
                 let (req, _) = req.into_parts();
                 Box::pin(async move {
-                    let response = HttpResponse::Forbidden()
-                        .json(serde_json::json!({"error": "Insufficient permissions"}))
-                        .map_into_boxed_body();
+                    let response = AppError::Forbidden("Personal access tokens cannot be used here".to_string()).error_response();
                     Ok(ServiceResponse::new(req, response))
                 })
             }
             None => {
                 let (req, _) = req.into_parts();
                 Box::pin(async move {
-                    let response = HttpResponse::Unauthorized()
-                        .json(serde_json::json!({"error": "Authentication required"}))
-                        .map_into_boxed_body();
+                    let response = AppError::Unauthorized("Authentication required".to_string()).error_response();
                     Ok(ServiceResponse::new(req, response))
                 })
             }
         }
     }
-}
\ No newline at end of file
+}
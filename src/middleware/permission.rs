@@ -0,0 +1,137 @@
+use actix_web::{dev::ServiceRequest, web, Error, FromRequest, HttpMessage, HttpRequest, ResponseError};
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Payload, Service, ServiceResponse, Transform};
+use futures_util::future::{ok, LocalBoxFuture, Ready};
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::utils::jwt::Claims;
+
+// 当前请求用户的权限集合，解析一次后缓存在请求扩展中
+#[derive(Clone, Debug)]
+pub struct UserPermissions(Arc<HashSet<String>>);
+
+impl UserPermissions {
+    pub fn has(&self, permission: &str) -> bool {
+        self.0.contains(permission)
+    }
+}
+
+async fn resolve_permissions(req: &HttpRequest) -> Result<UserPermissions, AppError> {
+    if let Some(cached) = req.extensions().get::<UserPermissions>() {
+        return Ok(cached.clone());
+    }
+
+    let (user_id, two_factor_pending, token_scopes) = req
+        .extensions()
+        .get::<Claims>()
+        .and_then(|c| {
+            let token_scopes = c.personal_token_id.map(|_| c.perms.clone());
+            c.sub.parse::<i64>().ok().map(|id| (id, c.two_factor_pending, token_scopes))
+        })
+        .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))?;
+
+    // 尚未按策略启用两步验证的会话没有任何权限
+    if two_factor_pending {
+        let permissions = UserPermissions(Arc::new(HashSet::new()));
+        req.extensions_mut().insert(permissions.clone());
+        return Ok(permissions);
+    }
+
+    let state = req
+        .app_data::<web::Data<AppState>>()
+        .cloned()
+        .expect("AppState must be registered as app data");
+
+    let mut granted = state.permission_cache.get_or_load(&state.pool, user_id).await?;
+    // 个人访问令牌只能使用令牌范围内、且用户当前仍拥有的权限
+    if let Some(scopes) = token_scopes {
+        granted = Arc::new(scopes.into_iter().filter(|scope| granted.contains(scope)).collect());
+    }
+
+    let permissions = UserPermissions(granted);
+    req.extensions_mut().insert(permissions.clone());
+    Ok(permissions)
+}
+
+// 在 handler 中按需读取当前用户权限
+impl FromRequest for UserPermissions {
+    type Error = AppError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move { resolve_permissions(&req).await })
+    }
+}
+
+pub struct RequirePermission {
+    required_permission: String,
+}
+
+impl RequirePermission {
+    pub fn new(permission: &str) -> Self {
+        RequirePermission {
+            required_permission: permission.to_string(),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequirePermissionMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequirePermissionMiddleware {
+            service: Rc::new(service),
+            required_permission: self.required_permission.clone(),
+        })
+    }
+}
+
+pub struct RequirePermissionMiddleware<S> {
+    service: Rc<S>,
+    required_permission: String,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let required_permission = self.required_permission.clone();
+
+        Box::pin(async move {
+            let error = match resolve_permissions(req.request()).await {
+                Ok(permissions) if permissions.has(&required_permission) => {
+                    let res = service.call(req).await?;
+                    return Ok(res.map_into_boxed_body());
+                }
+                Ok(_) => AppError::Forbidden("Insufficient permissions".to_string()),
+                Err(e) => e,
+            };
+
+            let (req, _) = req.into_parts();
+            Ok(ServiceResponse::new(req, error.error_response()))
+        })
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Serialize, Debug, FromRow)]
+pub struct UserIdentity {
+    pub id: i64,
+    pub user_id: i64,
+    pub provider: String,
+    pub provider_user_id: String,
+    pub provider_login: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct OAuthAuthorizeResponse {
+    pub authorize_url: String,
+}
+
+// 前端回调页面从 redirect_url 的查询参数中取得 code 和 state
+#[derive(Deserialize)]
+pub struct OAuthCallbackRequest {
+    pub code: String,
+    pub state: String,
+}
@@ -1,3 +1,9 @@
 pub mod task;
 pub mod task_submission;
-pub mod user;
\ No newline at end of file
+pub mod user;
+pub mod payout;
+pub mod wallet;
+pub mod two_factor;
+pub mod identity;
+pub mod personal_token;
+pub mod session;
\ No newline at end of file
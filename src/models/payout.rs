@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 奖励发放状态：
+// awaiting_address -> pending -> processing -> submitted -> confirmed
+//                                          \-> failed（可重试）
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    AwaitingAddress, // 尚未提供收款地址
+    Pending,
+    Processing,
+    Submitted, // 交易已签名并提交，等待链上确认
+    Confirmed,
+    Failed,
+}
+
+impl PayoutStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutStatus::AwaitingAddress => "awaiting_address",
+            PayoutStatus::Pending => "pending",
+            PayoutStatus::Processing => "processing",
+            PayoutStatus::Submitted => "submitted",
+            PayoutStatus::Confirmed => "confirmed",
+            PayoutStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "awaiting_address" => Some(PayoutStatus::AwaitingAddress),
+            "pending" => Some(PayoutStatus::Pending),
+            "processing" => Some(PayoutStatus::Processing),
+            "submitted" => Some(PayoutStatus::Submitted),
+            "confirmed" => Some(PayoutStatus::Confirmed),
+            "failed" => Some(PayoutStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Payout {
+    pub id: i64,
+    pub submission_id: i64,
+    pub user_id: i64,
+    pub recipient_address: Option<String>,
+    pub coin_type: String,
+    pub amount: u64, // 链上最小单位
+    pub status: PayoutStatus,
+    pub tx_digest: Option<String>,
+    pub error: String,
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+// 审核通过 / 手动创建发放时可附带收款地址
+#[derive(Deserialize, Default)]
+pub struct CreatePayoutRequest {
+    pub recipient_address: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PayoutQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub status: Option<PayoutStatus>,
+    pub user_id: Option<i64>,
+}
+
+impl PayoutQuery {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size.unwrap_or(10).clamp(1, 100)
+    }
+
+    pub fn offset(&self) -> u32 {
+        (self.page() - 1) * self.page_size()
+    }
+}
+
+#[derive(Serialize)]
+pub struct PayoutListResponse {
+    pub data: Vec<Payout>,
+    pub pagination: super::task::PaginationInfo,
+}
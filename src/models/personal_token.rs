@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug)]
+pub struct PersonalToken {
+    pub id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// scopes 为权限编码，只能选择自己当前拥有的权限；不设置 expires_in_days 表示永不过期
+#[derive(Deserialize)]
+pub struct CreatePersonalTokenRequest {
+    pub name: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub expires_in_days: Option<u32>,
+}
+
+// 完整令牌只在创建时返回一次
+#[derive(Serialize)]
+pub struct CreatePersonalTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub personal_token: PersonalToken,
+}
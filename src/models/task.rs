@@ -1,5 +1,58 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::FromRow;
+
+// 区分字段缺省（不修改）和显式的 null（清除）
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+// 任务生命周期：只有 published 的任务对学员可见并接受提交
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Draft,
+    Published,
+    Closed, // 停止接受新提交，已有提交继续审核
+    Archived, // 代替删除，保留提交和发放记录
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Draft => "draft",
+            TaskStatus::Published => "published",
+            TaskStatus::Closed => "closed",
+            TaskStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(TaskStatus::Draft),
+            "published" => Some(TaskStatus::Published),
+            "closed" => Some(TaskStatus::Closed),
+            "archived" => Some(TaskStatus::Archived),
+            _ => None,
+        }
+    }
+
+    // 状态转换表：关闭的任务可以重新发布，归档的任务恢复为草稿
+    pub fn can_transition_to(&self, next: TaskStatus) -> bool {
+        use TaskStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Published | Archived)
+                | (Published, Closed | Archived)
+                | (Closed, Published | Archived)
+                | (Archived, Draft)
+        )
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Task {
@@ -10,9 +63,15 @@ pub struct Task {
     pub name: String,
     pub reward_cny: i32,
     pub reward_token: String,
+    pub status: TaskStatus,
+    pub opens_at: Option<DateTime<Utc>>, // 为空表示发布后立即开放
+    pub closes_at: Option<DateTime<Utc>>, // 到期后由后台任务自动关闭
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>, // 当前用户的个人截止时间，仅在任务详情中返回
     pub updated_at: DateTime<Utc>,
 }
 
+// 新任务默认为草稿，也可以直接发布
 #[derive(Deserialize)]
 pub struct CreateTaskRequest {
     pub code: String,
@@ -20,6 +79,9 @@ pub struct CreateTaskRequest {
     pub reward_cny: i32,
     pub reward_token: String,
     pub description: String,
+    pub status: Option<TaskStatus>,
+    pub opens_at: Option<DateTime<Utc>>,
+    pub closes_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize)]
@@ -28,6 +90,24 @@ pub struct UpdateTaskRequest {
     pub reward_cny: Option<i32>,
     pub reward_token: Option<String>,
     pub description: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub opens_at: Option<Option<DateTime<Utc>>>, // null 表示清除
+    #[serde(default, deserialize_with = "nullable")]
+    pub closes_at: Option<Option<DateTime<Utc>>>,
+}
+
+// 为单个学员设置的截止时间，不能晚于任务的 closes_at
+#[derive(Serialize, Debug, FromRow)]
+pub struct TaskDeadline {
+    pub task_id: i64,
+    pub user_id: i64,
+    pub due_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct SetTaskDeadlineRequest {
+    pub due_at: DateTime<Utc>,
 }
 
 #[derive(Deserialize)]
@@ -35,6 +115,9 @@ pub struct TaskQuery {
     pub page: Option<u32>,
     pub page_size: Option<u32>,
     pub search: Option<String>,
+    pub status: Option<TaskStatus>, // 仅任务管理者可用，学员只能看到已发布的任务
+    pub open: Option<bool>, // 当前是否接受提交
+    pub closing_within: Option<String>, // 如 48h、30m、7d
 }
 
 impl TaskQuery {
@@ -43,7 +126,7 @@ impl TaskQuery {
     }
 
     pub fn page_size(&self) -> u32 {
-        self.page_size.unwrap_or(10).min(100).max(1)
+        self.page_size.unwrap_or(10).clamp(1, 100)
     }
 
     pub fn offset(&self) -> u32 {
@@ -1,13 +1,70 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+// 提交审核状态，状态之间的合法转换见 can_transition_to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    Pending,
+    InReview,
+    ChangesRequested, // 退回修改，学员修改后重新进入 pending
+    Approved,
+    Rejected,
+    Paid, // 奖励已在链上确认到账
+    Withdrawn, // 学员主动撤回
+}
+
+impl SubmissionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionStatus::Pending => "pending",
+            SubmissionStatus::InReview => "in_review",
+            SubmissionStatus::ChangesRequested => "changes_requested",
+            SubmissionStatus::Approved => "approved",
+            SubmissionStatus::Rejected => "rejected",
+            SubmissionStatus::Paid => "paid",
+            SubmissionStatus::Withdrawn => "withdrawn",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(SubmissionStatus::Pending),
+            "in_review" => Some(SubmissionStatus::InReview),
+            "changes_requested" => Some(SubmissionStatus::ChangesRequested),
+            "approved" => Some(SubmissionStatus::Approved),
+            "rejected" => Some(SubmissionStatus::Rejected),
+            "paid" => Some(SubmissionStatus::Paid),
+            "withdrawn" => Some(SubmissionStatus::Withdrawn),
+            _ => None,
+        }
+    }
+
+    // 状态转换表，rejected / paid / withdrawn 为终态
+    pub fn can_transition_to(&self, next: SubmissionStatus) -> bool {
+        use SubmissionStatus::*;
+        matches!(
+            (self, next),
+            (Pending, InReview | ChangesRequested | Approved | Rejected | Withdrawn)
+                | (InReview, ChangesRequested | Approved | Rejected)
+                | (ChangesRequested, Pending | Withdrawn)
+                | (Approved, Paid)
+        )
+    }
+
+    // 学员只能在这些状态下修改提交内容
+    pub fn is_editable(&self) -> bool {
+        matches!(self, SubmissionStatus::Pending | SubmissionStatus::ChangesRequested)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TaskSubmission {
     pub id: i64,
     pub task_id: i64,
     pub user_id: i64,
     pub pr_url: String,
-    pub status: String,
+    pub status: SubmissionStatus,
     pub note: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -16,14 +73,13 @@ pub struct TaskSubmission {
 #[derive(Deserialize)]
 pub struct CreateTaskSubmissionRequest {
     pub task_id: i64,
-    pub user_id: i64,
     pub pr_url: String,
     pub note: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct UpdateTaskSubmissionRequest {
-    pub status: Option<String>,
+    pub pr_url: Option<String>,
     pub note: Option<String>,
 }
 
@@ -31,7 +87,7 @@ pub struct UpdateTaskSubmissionRequest {
 pub struct TaskSubmissionQuery {
     pub page: Option<u32>,
     pub page_size: Option<u32>,
-    pub status: Option<String>,
+    pub status: Option<SubmissionStatus>,
 }
 
 impl TaskSubmissionQuery {
@@ -40,7 +96,7 @@ impl TaskSubmissionQuery {
     }
 
     pub fn page_size(&self) -> u32 {
-        self.page_size.unwrap_or(10).min(100).max(1)
+        self.page_size.unwrap_or(10).clamp(1, 100)
     }
 
     pub fn offset(&self) -> u32 {
@@ -48,13 +104,113 @@ impl TaskSubmissionQuery {
     }
 }
 
+// 用户各状态的提交数量
+#[derive(Serialize, Debug, Default)]
+pub struct SubmissionStats {
+    pub total: i64,
+    pub pending: i64,
+    pub in_review: i64,
+    pub changes_requested: i64,
+    pub approved: i64,
+    pub rejected: i64,
+    pub paid: i64,
+    pub withdrawn: i64,
+}
+
 #[derive(Serialize)]
 pub struct TaskSubmissionListResponse {
     pub data: Vec<TaskSubmission>,
     pub pagination: super::task::PaginationInfo,
 }
 
+// 审核通过时可附带审核意见和收款地址（启用链上发放时）
+#[derive(Deserialize, Default)]
+pub struct ApproveSubmissionRequest {
+    pub comment: Option<String>,
+    pub recipient_address: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct RejectSubmissionRequest {
     pub note: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RequestChangesRequest {
+    pub note: String,
+}
+
+// 状态变更记录，actor_id 为空表示由系统触发（如链上发放确认）
+#[derive(Serialize, Debug)]
+pub struct TaskSubmissionEvent {
+    pub id: i64,
+    pub submission_id: i64,
+    pub actor_id: Option<i64>,
+    pub actor_username: Option<String>,
+    pub from_status: Option<SubmissionStatus>,
+    pub to_status: SubmissionStatus,
+    pub comment: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SubmissionStatus::*;
+
+    const ALL: [SubmissionStatus; 7] = [Pending, InReview, ChangesRequested, Approved, Rejected, Paid, Withdrawn];
+
+    // 完整的合法转换表，其余组合都应被拒绝
+    const ALLOWED: [(SubmissionStatus, SubmissionStatus); 11] = [
+        (Pending, InReview),
+        (Pending, ChangesRequested),
+        (Pending, Approved),
+        (Pending, Rejected),
+        (Pending, Withdrawn),
+        (InReview, ChangesRequested),
+        (InReview, Approved),
+        (InReview, Rejected),
+        (ChangesRequested, Pending),
+        (ChangesRequested, Withdrawn),
+        (Approved, Paid),
+    ];
+
+    #[test]
+    fn transition_table_matches_every_pair() {
+        for from in ALL {
+            for to in ALL {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    ALLOWED.contains(&(from, to)),
+                    "{} -> {}",
+                    from.as_str(),
+                    to.as_str()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejected_paid_and_withdrawn_are_terminal() {
+        for from in [Rejected, Paid, Withdrawn] {
+            assert!(ALL.iter().all(|to| !from.can_transition_to(*to)), "{}", from.as_str());
+        }
+    }
+
+    #[test]
+    fn approved_only_moves_to_paid() {
+        let next: Vec<SubmissionStatus> = ALL.into_iter().filter(|to| Approved.can_transition_to(*to)).collect();
+        assert_eq!(next, vec![Paid]);
+    }
+
+    #[test]
+    fn as_str_and_parse_round_trip() {
+        for status in ALL {
+            assert_eq!(SubmissionStatus::parse(status.as_str()), Some(status));
+            // 与 JSON 中的表示一致
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+        assert_eq!(SubmissionStatus::parse("Pending"), None);
+        assert_eq!(SubmissionStatus::parse(""), None);
+    }
 }
\ No newline at end of file
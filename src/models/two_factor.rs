@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+// 验证器 App 扫描 otpauth_uri，或手动输入 secret
+#[derive(Serialize)]
+pub struct TwoFactorSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+// 恢复码只在启用时返回一次
+#[derive(Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+// code 为 6 位 TOTP 验证码或恢复码
+#[derive(Deserialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct TwoFactorLoginRequest {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+// 密码验证通过、等待提交验证码时的登录响应
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_in: i64, // 秒
+}
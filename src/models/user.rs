@@ -1,14 +1,25 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
+use crate::models::task::PaginationInfo;
+use crate::models::task_submission::SubmissionStats;
+use crate::models::two_factor::TwoFactorChallengeResponse;
+use crate::models::wallet::UserWallet;
+
+// sys_user.status 取值
+pub const USER_STATUS_DISABLED: i8 = 0;
+pub const USER_STATUS_ACTIVE: i8 = 1;
+pub const USER_STATUS_PENDING_VERIFICATION: i8 = 2;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct SysUser {
     pub id: i64,
     pub username: String,
+    pub email: Option<String>,
     pub password_hash: String,
     pub salt: String,
     pub status: i8,
+    pub locked_until: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -18,6 +29,7 @@ pub struct SysRole {
     pub role_key: String,
     pub role_name: String,
     pub status: i8,
+    pub is_builtin: bool, // 内置角色不可修改或删除
     pub created_at: DateTime<Utc>,
 }
 
@@ -26,15 +38,70 @@ pub struct SysPermission {
     pub id: i64,
     pub perm_key: String,
     pub perm_name: String,
+    pub is_builtin: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize)]
+pub struct RoleWithPermissions {
+    #[serde(flatten)]
+    pub role: SysRole,
+    pub permissions: Vec<SysPermission>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRoleRequest {
+    pub role_key: String,
+    pub role_name: String,
+    #[serde(default)]
+    pub permission_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub role_name: Option<String>,
+    pub status: Option<i8>, // 0 禁用 / 1 启用
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetRolePermissionsRequest {
+    pub permission_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePermissionRequest {
+    pub perm_key: String,
+    pub perm_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePermissionRequest {
+    pub perm_name: String,
+}
+
+// 当前用户的资料，每次从数据库读取
+#[derive(Debug, Serialize)]
 pub struct UserWithRoles {
     pub id: i64,
     pub username: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub avatar_url: Option<String>,
     pub status: i8,
+    pub two_factor_enabled: bool,
     pub roles: Vec<String>,
     pub permissions: Vec<String>,
+    pub wallets: Vec<UserWallet>,
+    pub submission_stats: SubmissionStats,
+    pub created_at: DateTime<Utc>,
+}
+
+// 只修改提供的字段，空字符串表示清除；修改邮箱后需重新验证
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -46,7 +113,23 @@ pub struct LoginRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginResponse {
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64, // access token 有效期（秒）
     pub user: UserResponse,
+    pub two_factor_setup_required: bool, // 为 true 时需先启用两步验证，令牌不带任何权限
+}
+
+// 登录结果：直接签发令牌，或要求继续提交两步验证码
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginOutcome {
+    Tokens(LoginResponse),
+    TwoFactorRequired(TwoFactorChallengeResponse),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,4 +145,104 @@ pub struct CreateUserRequest {
     pub username: String,
     pub password: String,
     pub role_ids: Vec<i64>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailQuery {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+// 管理员用户列表查询
+#[derive(Debug, Deserialize)]
+pub struct UserQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub search: Option<String>, // 匹配用户名或邮箱
+    pub status: Option<i8>,
+}
+
+impl UserQuery {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size.unwrap_or(20).clamp(1, 100)
+    }
+
+    pub fn offset(&self) -> u32 {
+        (self.page() - 1) * self.page_size()
+    }
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RoleSummary {
+    pub id: i64,
+    pub role_key: String,
+    pub role_name: String,
+}
+
+// 管理员查看的用户信息
+#[derive(Debug, Serialize)]
+pub struct AdminUserResponse {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub avatar_url: Option<String>,
+    pub status: i8,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub two_factor_enabled: bool,
+    pub roles: Vec<RoleSummary>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct UserListResponse {
+    pub data: Vec<AdminUserResponse>,
+    pub pagination: PaginationInfo,
+}
+
+// 只修改提供的字段；email 为空字符串时清除邮箱
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetUserRolesRequest {
+    pub role_ids: Vec<i64>,
+}
+
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Serialize, Debug, FromRow)]
+pub struct UserWallet {
+    pub id: i64,
+    pub user_id: i64,
+    pub address: String,
+    pub is_primary: bool, // 奖励发放默认使用的地址
+    pub verified_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 钱包需要对 message 做 signPersonalMessage
+#[derive(Serialize)]
+pub struct WalletChallengeResponse {
+    pub nonce: String,
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct BindWalletRequest {
+    pub address: String,
+    pub nonce: String,
+    pub signature: String, // base64(flag || signature || public_key)
+}
+
+#[derive(Deserialize)]
+pub struct WalletLoginChallengeQuery {
+    pub address: String,
+}
+
+#[derive(Deserialize)]
+pub struct WalletLoginRequest {
+    pub address: String,
+    pub nonce: String,
+    pub signature: String, // base64(flag || signature || public_key)
+}
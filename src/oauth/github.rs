@@ -0,0 +1,257 @@
+use reqwest::Url;
+use serde::Deserialize;
+use crate::config::settings::GithubOAuthSettings;
+use super::{OAuthError, OAuthProfile};
+
+pub const GITHUB_PROVIDER: &str = "github";
+
+// GitHub API 要求请求带 User-Agent
+const USER_AGENT: &str = "learn2earn";
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubUser {
+    id: i64,
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct GithubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+// GitHub OAuth App 授权码流程
+pub struct GithubOAuthClient {
+    http: reqwest::Client,
+    settings: GithubOAuthSettings,
+}
+
+impl GithubOAuthClient {
+    pub fn new(settings: &GithubOAuthSettings) -> Self {
+        GithubOAuthClient {
+            http: reqwest::Client::new(),
+            settings: settings.clone(),
+        }
+    }
+
+    // 用户浏览器跳转的授权地址
+    pub fn authorize_url(&self, state: &str) -> Result<String, OAuthError> {
+        let scope = self.settings.scopes.join(" ");
+        let url = Url::parse_with_params(
+            &self.settings.authorize_url,
+            [
+                ("client_id", self.settings.client_id.as_str()),
+                ("redirect_uri", self.settings.redirect_url.as_str()),
+                ("scope", scope.as_str()),
+                ("state", state),
+                ("allow_signup", "true"),
+            ],
+        )
+        .map_err(|e| OAuthError::Provider(format!("invalid authorize_url: {}", e)))?;
+        Ok(url.to_string())
+    }
+
+    // 用授权码换取 access token 并读取账号信息
+    pub async fn authenticate(&self, code: &str) -> Result<OAuthProfile, OAuthError> {
+        let access_token = self.exchange_code(code).await?;
+
+        let user: GithubUser = self.get(&self.settings.user_url, &access_token).await?;
+        let emails: Vec<GithubEmail> = self.get(&self.settings.emails_url, &access_token).await?;
+        let email = emails
+            .into_iter()
+            .find(|email| email.primary && email.verified)
+            .map(|email| email.email.to_lowercase());
+
+        Ok(OAuthProfile {
+            provider_user_id: user.id.to_string(),
+            login: user.login,
+            email,
+        })
+    }
+
+    async fn exchange_code(&self, code: &str) -> Result<String, OAuthError> {
+        let response: TokenResponse = self
+            .http
+            .post(&self.settings.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .form(&[
+                ("client_id", self.settings.client_id.as_str()),
+                ("client_secret", self.settings.client_secret.as_str()),
+                ("code", code),
+                ("redirect_uri", self.settings.redirect_url.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| OAuthError::Provider(format!("token exchange failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| OAuthError::Provider(format!("invalid token response: {}", e)))?;
+
+        // GitHub 对无效授权码返回 200 和 error 字段
+        if let Some(error) = response.error {
+            return Err(OAuthError::Rejected(response.error_description.unwrap_or(error)));
+        }
+        response
+            .access_token
+            .ok_or_else(|| OAuthError::Provider("token response has no access_token".to_string()))
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, url: &str, access_token: &str) -> Result<T, OAuthError> {
+        self.http
+            .get(url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .header(reqwest::header::USER_AGENT, USER_AGENT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| OAuthError::Provider(format!("GET {} failed: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| OAuthError::Provider(format!("GET {}: invalid response: {}", url, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use actix_web::dev::ServerHandle;
+    use serde_json::{json, Value};
+    use super::*;
+
+    const ACCESS_TOKEN: &str = "gho_test";
+
+    // 模拟 GitHub 的本地服务，token 端点返回给定的响应体
+    struct StubGithub {
+        handle: ServerHandle,
+        settings: GithubOAuthSettings,
+    }
+
+    async fn stub_github(token_response: Value, emails: Value) -> StubGithub {
+        let server = HttpServer::new(move || {
+            let token_response = token_response.clone();
+            let emails = emails.clone();
+            App::new()
+                .route("/login/oauth/access_token", web::post().to(move || {
+                    let body = token_response.clone();
+                    async move { HttpResponse::Ok().json(body) }
+                }))
+                .route("/user", web::get().to(|req: HttpRequest| async move {
+                    authorized(&req, json!({ "id": 42, "login": "octocat" }))
+                }))
+                .route("/user/emails", web::get().to(move |req: HttpRequest| {
+                    let body = emails.clone();
+                    async move { authorized(&req, body) }
+                }))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("bind stub server");
+
+        let base = format!("http://{}", server.addrs()[0]);
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+
+        StubGithub {
+            handle,
+            settings: GithubOAuthSettings {
+                enabled: true,
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+                redirect_url: "http://localhost/callback".to_string(),
+                token_url: format!("{}/login/oauth/access_token", base),
+                user_url: format!("{}/user", base),
+                emails_url: format!("{}/user/emails", base),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn authorized(req: &HttpRequest, body: Value) -> HttpResponse {
+        let expected = format!("Bearer {}", ACCESS_TOKEN);
+        match req.headers().get("Authorization").and_then(|value| value.to_str().ok()) {
+            Some(value) if value == expected => HttpResponse::Ok().json(body),
+            _ => HttpResponse::Unauthorized().finish(),
+        }
+    }
+
+    fn token() -> Value {
+        json!({ "access_token": ACCESS_TOKEN, "token_type": "bearer", "scope": "read:user,user:email" })
+    }
+
+    #[actix_web::test]
+    async fn error_in_ok_token_response_is_rejected() {
+        let stub = stub_github(
+            json!({ "error": "bad_verification_code", "error_description": "The code passed is incorrect or expired." }),
+            json!([]),
+        )
+        .await;
+
+        let result = GithubOAuthClient::new(&stub.settings).authenticate("expired").await;
+        assert!(matches!(result, Err(OAuthError::Rejected(ref message)) if message == "The code passed is incorrect or expired."));
+        stub.handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn error_without_description_is_rejected() {
+        let stub = stub_github(json!({ "error": "access_denied" }), json!([])).await;
+
+        let result = GithubOAuthClient::new(&stub.settings).authenticate("denied").await;
+        assert!(matches!(result, Err(OAuthError::Rejected(ref message)) if message == "access_denied"));
+        stub.handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn missing_access_token_is_a_provider_error() {
+        let stub = stub_github(json!({ "token_type": "bearer" }), json!([])).await;
+
+        let result = GithubOAuthClient::new(&stub.settings).authenticate("code").await;
+        assert!(matches!(result, Err(OAuthError::Provider(ref message)) if message.contains("no access_token")));
+        stub.handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn picks_primary_verified_email() {
+        let stub = stub_github(
+            token(),
+            json!([
+                { "email": "other@example.com", "primary": false, "verified": true },
+                { "email": "Octo.Cat@Example.com", "primary": true, "verified": true },
+            ]),
+        )
+        .await;
+
+        let profile = GithubOAuthClient::new(&stub.settings).authenticate("code").await.unwrap();
+        assert_eq!(profile.provider_user_id, "42");
+        assert_eq!(profile.login, "octocat");
+        assert_eq!(profile.email.as_deref(), Some("octo.cat@example.com"));
+        stub.handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn unverified_primary_email_is_ignored() {
+        let stub = stub_github(
+            token(),
+            json!([
+                { "email": "primary@example.com", "primary": true, "verified": false },
+                { "email": "verified@example.com", "primary": false, "verified": true },
+            ]),
+        )
+        .await;
+
+        let profile = GithubOAuthClient::new(&stub.settings).authenticate("code").await.unwrap();
+        assert_eq!(profile.email, None);
+        stub.handle.stop(false).await;
+    }
+}
@@ -0,0 +1,40 @@
+pub mod github;
+
+use std::fmt;
+use crate::error::AppError;
+
+#[derive(Debug)]
+pub enum OAuthError {
+    // 授权码无效、过期或用户拒绝授权
+    Rejected(String),
+    // 网络错误或提供方返回了无法解析的响应
+    Provider(String),
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OAuthError::Rejected(message) => write!(f, "authorization rejected: {}", message),
+            OAuthError::Provider(message) => write!(f, "OAuth provider error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for OAuthError {}
+
+impl From<OAuthError> for AppError {
+    fn from(e: OAuthError) -> Self {
+        match e {
+            OAuthError::Rejected(_) => AppError::Unauthorized(e.to_string()),
+            OAuthError::Provider(_) => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+// 第三方账号信息
+#[derive(Debug, Clone)]
+pub struct OAuthProfile {
+    pub provider_user_id: String,
+    pub login: String,
+    pub email: Option<String>, // 仅包含提供方已验证的主邮箱
+}
@@ -0,0 +1,59 @@
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use super::PayoutError;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferRequest {
+    pub sender: String,
+    pub recipient: String,
+    pub coin_type: String,
+    pub amount: u64,
+    pub gas_budget: u64,
+}
+
+// 链上交易的执行状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    Success,
+    Failure(String),
+    // 节点尚未返回执行结果
+    Pending,
+}
+
+// 已签名、尚未提交的交易；digest 在本地计算，提交前即可落库
+#[derive(Debug, Clone)]
+pub struct PreparedTransfer {
+    pub tx_bytes: Vec<u8>,
+    pub signature: String,
+    pub digest: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    pub digest: String,
+    pub status: TxStatus,
+}
+
+// SUI 节点访问接口：真实节点走 JSON-RPC，本地开发/测试可替换为 mock
+pub trait SuiRpc: Send + Sync {
+    // 由节点构建转账交易，返回待签名的交易字节
+    fn build_transfer(&self, request: TransferRequest) -> BoxFuture<'_, Result<Vec<u8>, PayoutError>>;
+
+    fn execute_transaction(
+        &self,
+        tx_bytes: Vec<u8>,
+        signature: String,
+    ) -> BoxFuture<'_, Result<ExecutionResult, PayoutError>>;
+
+    fn transaction_status(&self, digest: String) -> BoxFuture<'_, Result<TxStatus, PayoutError>>;
+}
+
+// SUI 交易 digest = base58(blake2b256("TransactionData::" || tx_bytes))
+pub fn transaction_digest(tx_bytes: &[u8]) -> String {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(b"TransactionData::");
+    hasher.update(tx_bytes);
+    bs58::encode(hasher.finalize()).into_string()
+}
@@ -0,0 +1,161 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::future::BoxFuture;
+use serde_json::{json, Value};
+use super::client::{ExecutionResult, SuiRpc, TransferRequest, TxStatus};
+use super::PayoutError;
+
+// 单次转账最多合并的 coin 对象数量
+const MAX_INPUT_COINS: u32 = 50;
+
+// 基于 SUI 全节点 JSON-RPC 的实现，交易由节点的 unsafe_pay* 接口构建
+pub struct JsonRpcSuiClient {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl JsonRpcSuiClient {
+    pub fn new(url: &str) -> Self {
+        JsonRpcSuiClient {
+            http: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, PayoutError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response: Value = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| PayoutError::Rpc(format!("{}: {}", method, e)))?
+            .json()
+            .await
+            .map_err(|e| PayoutError::Rpc(format!("{}: invalid response: {}", method, e)))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(PayoutError::Rpc(format!("{}: {}", method, error)));
+        }
+
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| PayoutError::Rpc(format!("{}: missing result", method)))
+    }
+
+    async fn build(&self, request: TransferRequest) -> Result<Vec<u8>, PayoutError> {
+        let coins = self
+            .call(
+                "suix_getCoins",
+                json!([request.sender, request.coin_type, null, MAX_INPUT_COINS]),
+            )
+            .await?;
+
+        let coin_ids: Vec<&str> = coins["data"]
+            .as_array()
+            .map(|data| data.iter().filter_map(|coin| coin["coinObjectId"].as_str()).collect())
+            .unwrap_or_default();
+
+        if coin_ids.is_empty() {
+            return Err(PayoutError::InsufficientFunds(request.coin_type));
+        }
+
+        let amount = request.amount.to_string();
+        let gas_budget = request.gas_budget.to_string();
+        let result = if is_sui_coin(&request.coin_type) {
+            self.call(
+                "unsafe_paySui",
+                json!([request.sender, coin_ids, [request.recipient], [amount], gas_budget]),
+            )
+            .await?
+        } else {
+            // gas 对象传 null，由节点从出款账户中选择
+            self.call(
+                "unsafe_pay",
+                json!([request.sender, coin_ids, [request.recipient], [amount], null, gas_budget]),
+            )
+            .await?
+        };
+
+        let tx_bytes = result["txBytes"]
+            .as_str()
+            .ok_or_else(|| PayoutError::Rpc("unsafe_pay: missing txBytes".to_string()))?;
+
+        BASE64
+            .decode(tx_bytes)
+            .map_err(|e| PayoutError::Rpc(format!("unsafe_pay: invalid txBytes: {}", e)))
+    }
+
+    async fn execute(&self, tx_bytes: Vec<u8>, signature: String) -> Result<ExecutionResult, PayoutError> {
+        let result = self
+            .call(
+                "sui_executeTransactionBlock",
+                json!([BASE64.encode(tx_bytes), [signature], {"showEffects": true}, "WaitForLocalExecution"]),
+            )
+            .await?;
+
+        let digest = result["digest"]
+            .as_str()
+            .ok_or_else(|| PayoutError::Rpc("sui_executeTransactionBlock: missing digest".to_string()))?
+            .to_string();
+
+        Ok(ExecutionResult {
+            digest,
+            status: parse_effects_status(&result),
+        })
+    }
+
+    async fn status(&self, digest: String) -> Result<TxStatus, PayoutError> {
+        let result = self
+            .call("sui_getTransactionBlock", json!([digest, {"showEffects": true}]))
+            .await?;
+
+        Ok(parse_effects_status(&result))
+    }
+}
+
+impl SuiRpc for JsonRpcSuiClient {
+    fn build_transfer(&self, request: TransferRequest) -> BoxFuture<'_, Result<Vec<u8>, PayoutError>> {
+        Box::pin(self.build(request))
+    }
+
+    fn execute_transaction(
+        &self,
+        tx_bytes: Vec<u8>,
+        signature: String,
+    ) -> BoxFuture<'_, Result<ExecutionResult, PayoutError>> {
+        Box::pin(self.execute(tx_bytes, signature))
+    }
+
+    fn transaction_status(&self, digest: String) -> BoxFuture<'_, Result<TxStatus, PayoutError>> {
+        Box::pin(self.status(digest))
+    }
+}
+
+fn parse_effects_status(result: &Value) -> TxStatus {
+    let status = &result["effects"]["status"];
+    match status["status"].as_str() {
+        Some("success") => TxStatus::Success,
+        Some("failure") => TxStatus::Failure(
+            status["error"].as_str().unwrap_or("transaction failed").to_string(),
+        ),
+        _ => TxStatus::Pending,
+    }
+}
+
+// 原生 SUI 使用 unsafe_paySui，其他代币使用 unsafe_pay
+fn is_sui_coin(coin_type: &str) -> bool {
+    coin_type
+        .strip_suffix("::sui::SUI")
+        .and_then(|address| address.strip_prefix("0x"))
+        .is_some_and(|address| address.trim_start_matches('0') == "2")
+}
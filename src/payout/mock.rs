@@ -0,0 +1,97 @@
+use futures_util::future::{ready, BoxFuture};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use super::client::{transaction_digest, ExecutionResult, SuiRpc, TransferRequest, TxStatus};
+use super::PayoutError;
+
+// 内存中的 SUI 节点，用于本地开发和测试：未预设结果时所有转账都会立即成功
+#[derive(Default)]
+pub struct MockSuiRpc {
+    sequence: AtomicU64,
+    transactions: Mutex<HashMap<String, TxStatus>>,
+    script: Mutex<MockScript>,
+}
+
+// 测试中预设的节点行为，按调用顺序依次使用
+#[derive(Default)]
+struct MockScript {
+    build_failures: VecDeque<String>,
+    execution_statuses: VecDeque<TxStatus>,
+}
+
+impl MockSuiRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+impl MockSuiRpc {
+    // 下一次构建交易失败
+    pub fn fail_next_build(&self, message: &str) {
+        self.script.lock().unwrap().build_failures.push_back(message.to_string());
+    }
+
+    // 下一笔提交的交易返回指定的执行状态
+    pub fn queue_execution_status(&self, status: TxStatus) {
+        self.script.lock().unwrap().execution_statuses.push_back(status);
+    }
+
+    // 模拟交易在链上的状态变化
+    pub fn set_status(&self, digest: &str, status: TxStatus) {
+        self.transactions.lock().unwrap().insert(digest.to_string(), status);
+    }
+
+    // 已提交的交易数量
+    pub fn executed(&self) -> usize {
+        self.transactions.lock().unwrap().len()
+    }
+}
+
+impl SuiRpc for MockSuiRpc {
+    fn build_transfer(&self, request: TransferRequest) -> BoxFuture<'_, Result<Vec<u8>, PayoutError>> {
+        if let Some(message) = self.script.lock().expect("mock script lock poisoned").build_failures.pop_front() {
+            return Box::pin(ready(Err(PayoutError::Rpc(format!("mock: {}", message)))));
+        }
+
+        // 序号保证相同参数的转账也得到不同的交易字节
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst);
+        let tx_bytes = serde_json::to_vec(&(sequence, &request))
+            .map_err(|e| PayoutError::Rpc(format!("mock: {}", e)));
+        Box::pin(ready(tx_bytes))
+    }
+
+    fn execute_transaction(
+        &self,
+        tx_bytes: Vec<u8>,
+        _signature: String,
+    ) -> BoxFuture<'_, Result<ExecutionResult, PayoutError>> {
+        let digest = transaction_digest(&tx_bytes);
+        let status = self
+            .script
+            .lock()
+            .expect("mock script lock poisoned")
+            .execution_statuses
+            .pop_front()
+            .unwrap_or(TxStatus::Success);
+
+        self.transactions
+            .lock()
+            .expect("mock transactions lock poisoned")
+            .insert(digest.clone(), status.clone());
+
+        Box::pin(ready(Ok(ExecutionResult { digest, status })))
+    }
+
+    fn transaction_status(&self, digest: String) -> BoxFuture<'_, Result<TxStatus, PayoutError>> {
+        let status = self
+            .transactions
+            .lock()
+            .expect("mock transactions lock poisoned")
+            .get(&digest)
+            .cloned()
+            .ok_or_else(|| PayoutError::Rpc(format!("mock: unknown transaction {}", digest)));
+        Box::pin(ready(status))
+    }
+}
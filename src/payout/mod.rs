@@ -0,0 +1,183 @@
+pub mod client;
+pub mod json_rpc;
+pub mod mock;
+pub mod signer;
+
+use std::fmt;
+use std::sync::Arc;
+use crate::config::settings::{PayoutBackend, PayoutSettings};
+use crate::error::AppError;
+use client::{transaction_digest, ExecutionResult, PreparedTransfer, SuiRpc, TransferRequest, TxStatus};
+use json_rpc::JsonRpcSuiClient;
+use mock::MockSuiRpc;
+use signer::{Ed25519Signer, PayoutSigner};
+
+#[derive(Debug)]
+pub enum PayoutError {
+    Rpc(String),
+    Signer(String),
+    InsufficientFunds(String),
+}
+
+impl fmt::Display for PayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayoutError::Rpc(message) => write!(f, "SUI RPC error: {}", message),
+            PayoutError::Signer(message) => write!(f, "signer error: {}", message),
+            PayoutError::InsufficientFunds(coin_type) => {
+                write!(f, "payout account has no {} coins", coin_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PayoutError {}
+
+impl From<PayoutError> for AppError {
+    fn from(e: PayoutError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+// 出款客户端：组合节点访问和签名，两者都可以单独替换
+#[derive(Clone)]
+pub struct PayoutClient {
+    rpc: Arc<dyn SuiRpc>,
+    signer: Arc<dyn PayoutSigner>,
+    gas_budget: u64,
+}
+
+impl PayoutClient {
+    pub fn new(rpc: Arc<dyn SuiRpc>, signer: Arc<dyn PayoutSigner>, gas_budget: u64) -> Self {
+        PayoutClient { rpc, signer, gas_budget }
+    }
+
+    pub fn from_settings(settings: &PayoutSettings) -> Result<Self, PayoutError> {
+        let client = match settings.backend {
+            PayoutBackend::JsonRpc => PayoutClient::new(
+                Arc::new(JsonRpcSuiClient::new(&settings.rpc_url)),
+                Arc::new(Ed25519Signer::from_base64(&settings.signer_key)?),
+                settings.gas_budget,
+            ),
+            PayoutBackend::Mock => {
+                let signer = if settings.signer_key.is_empty() {
+                    Ed25519Signer::generate()
+                } else {
+                    Ed25519Signer::from_base64(&settings.signer_key)?
+                };
+                PayoutClient::new(Arc::new(MockSuiRpc::new()), Arc::new(signer), settings.gas_budget)
+            }
+        };
+        Ok(client)
+    }
+
+    pub fn sender_address(&self) -> String {
+        self.signer.address()
+    }
+
+    // 构建并签名一笔转账，返回可提交的交易
+    pub async fn prepare_transfer(
+        &self,
+        recipient: &str,
+        coin_type: &str,
+        amount: u64,
+    ) -> Result<PreparedTransfer, PayoutError> {
+        let tx_bytes = self
+            .rpc
+            .build_transfer(TransferRequest {
+                sender: self.signer.address(),
+                recipient: recipient.to_string(),
+                coin_type: coin_type.to_string(),
+                amount,
+                gas_budget: self.gas_budget,
+            })
+            .await?;
+
+        let signature = self.signer.sign_transaction(&tx_bytes)?;
+        let digest = transaction_digest(&tx_bytes);
+        Ok(PreparedTransfer { tx_bytes, signature, digest })
+    }
+
+    pub async fn submit(&self, prepared: PreparedTransfer) -> Result<ExecutionResult, PayoutError> {
+        self.rpc
+            .execute_transaction(prepared.tx_bytes, prepared.signature)
+            .await
+    }
+
+    pub async fn transaction_status(&self, digest: &str) -> Result<TxStatus, PayoutError> {
+        self.rpc.transaction_status(digest.to_string()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPIENT: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    const COIN_TYPE: &str = "0x2::sui::SUI";
+
+    fn mock_client() -> (Arc<MockSuiRpc>, PayoutClient) {
+        let rpc = Arc::new(MockSuiRpc::new());
+        let client = PayoutClient::new(rpc.clone(), Arc::new(Ed25519Signer::generate()), 1_000);
+        (rpc, client)
+    }
+
+    #[actix_web::test]
+    async fn prepare_submit_and_confirm() {
+        let (rpc, client) = mock_client();
+
+        let prepared = client.prepare_transfer(RECIPIENT, COIN_TYPE, 500).await.unwrap();
+        assert_eq!(prepared.digest, transaction_digest(&prepared.tx_bytes));
+        assert!(!prepared.signature.is_empty());
+
+        let digest = prepared.digest.clone();
+        let result = client.submit(prepared).await.unwrap();
+        assert_eq!(result.digest, digest);
+        assert_eq!(result.status, TxStatus::Success);
+        assert_eq!(client.transaction_status(&digest).await.unwrap(), TxStatus::Success);
+        assert_eq!(rpc.executed(), 1);
+    }
+
+    #[actix_web::test]
+    async fn pending_transaction_confirms_later() {
+        let (rpc, client) = mock_client();
+        rpc.queue_execution_status(TxStatus::Pending);
+
+        let prepared = client.prepare_transfer(RECIPIENT, COIN_TYPE, 500).await.unwrap();
+        let result = client.submit(prepared).await.unwrap();
+        assert_eq!(result.status, TxStatus::Pending);
+        assert_eq!(client.transaction_status(&result.digest).await.unwrap(), TxStatus::Pending);
+
+        rpc.set_status(&result.digest, TxStatus::Success);
+        assert_eq!(client.transaction_status(&result.digest).await.unwrap(), TxStatus::Success);
+    }
+
+    #[actix_web::test]
+    async fn failed_transfer_can_be_retried() {
+        let (rpc, client) = mock_client();
+
+        // 构建失败时交易尚未提交
+        rpc.fail_next_build("node unavailable");
+        let error = client.prepare_transfer(RECIPIENT, COIN_TYPE, 500).await.unwrap_err();
+        assert!(matches!(error, PayoutError::Rpc(ref message) if message.contains("node unavailable")));
+        assert_eq!(rpc.executed(), 0);
+
+        // 链上执行失败后重试，重试得到新的交易
+        rpc.queue_execution_status(TxStatus::Failure("InsufficientGas".to_string()));
+        let first = client.prepare_transfer(RECIPIENT, COIN_TYPE, 500).await.unwrap();
+        let first = client.submit(first).await.unwrap();
+        assert_eq!(first.status, TxStatus::Failure("InsufficientGas".to_string()));
+
+        let retry = client.prepare_transfer(RECIPIENT, COIN_TYPE, 500).await.unwrap();
+        assert_ne!(retry.digest, first.digest);
+        let retry = client.submit(retry).await.unwrap();
+        assert_eq!(retry.status, TxStatus::Success);
+        assert_eq!(rpc.executed(), 2);
+    }
+
+    #[actix_web::test]
+    async fn unknown_digest_is_an_error() {
+        let (_, client) = mock_client();
+        assert!(client.transaction_status("unknown").await.is_err());
+    }
+}
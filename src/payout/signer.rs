@@ -0,0 +1,70 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use blake2::Digest;
+use ed25519_dalek::{Signer, SigningKey};
+use rand::{thread_rng, RngCore};
+use crate::utils::sui::{sui_address_from_public_key, Blake2b256, ED25519_FLAG};
+use super::PayoutError;
+
+// 交易签名使用的 intent 前缀：TransactionData / V0 / Sui
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
+// 负责对交易字节签名的出款账户，可替换为 KMS/HSM 等实现
+pub trait PayoutSigner: Send + Sync {
+    fn address(&self) -> String;
+    // 返回 SUI 序列化签名（base64(flag || signature || public_key)）
+    fn sign_transaction(&self, tx_bytes: &[u8]) -> Result<String, PayoutError>;
+}
+
+pub struct Ed25519Signer {
+    key: SigningKey,
+}
+
+impl Ed25519Signer {
+    // 支持 32 字节私钥或 `sui keytool export` 导出的 flag || 私钥（33 字节），均为 base64
+    pub fn from_base64(encoded: &str) -> Result<Self, PayoutError> {
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| PayoutError::Signer(format!("invalid signer key encoding: {}", e)))?;
+
+        let secret: [u8; 32] = match bytes.as_slice() {
+            [ED25519_FLAG, rest @ ..] if rest.len() == 32 => rest.try_into().unwrap_or_default(),
+            raw if raw.len() == 32 => raw.try_into().unwrap_or_default(),
+            _ => return Err(PayoutError::Signer("signer key must be an Ed25519 private key".to_string())),
+        };
+
+        Ok(Ed25519Signer {
+            key: SigningKey::from_bytes(&secret),
+        })
+    }
+
+    // 随机生成的临时账户，仅用于 mock 链
+    pub fn generate() -> Self {
+        let mut secret = [0u8; 32];
+        thread_rng().fill_bytes(&mut secret);
+        Ed25519Signer {
+            key: SigningKey::from_bytes(&secret),
+        }
+    }
+}
+
+impl PayoutSigner for Ed25519Signer {
+    fn address(&self) -> String {
+        sui_address_from_public_key(&self.key.verifying_key())
+    }
+
+    fn sign_transaction(&self, tx_bytes: &[u8]) -> Result<String, PayoutError> {
+        let mut hasher = Blake2b256::new();
+        hasher.update(TRANSACTION_INTENT);
+        hasher.update(tx_bytes);
+        let digest = hasher.finalize();
+
+        let signature = self.key.sign(&digest);
+
+        let mut serialized = Vec::with_capacity(1 + 64 + 32);
+        serialized.push(ED25519_FLAG);
+        serialized.extend_from_slice(&signature.to_bytes());
+        serialized.extend_from_slice(self.key.verifying_key().as_bytes());
+        Ok(BASE64.encode(serialized))
+    }
+}
@@ -1,25 +1,125 @@
 use actix_web::web;
-use crate::controllers::auth_controller;
-use crate::middleware::auth::RequireRole;
+use crate::controllers::{
+    auth_controller, identity_controller, profile_controller, role_controller, session_controller, user_controller,
+};
+use crate::middleware::auth::RequireSession;
+use crate::middleware::permission::RequirePermission;
 
+// 公开路由逐个注册为资源：若使用 "/api/auth" scope 会吞掉受保护的 /api/auth/* 路由
 pub fn configure_auth_routes(cfg: &mut web::ServiceConfig) {
-    println!("configure_auth_routes");
-    cfg.service(
-        web::scope("/api/auth")
-            .route("/login", web::post().to(auth_controller::login))
-    );
+    cfg.route("/api/auth/login", web::post().to(auth_controller::login))
+        .route("/api/auth/login/2fa", web::post().to(auth_controller::login_two_factor))
+        .route("/api/auth/wallet/challenge", web::get().to(auth_controller::wallet_challenge))
+        .route("/api/auth/wallet/login", web::post().to(auth_controller::wallet_login))
+        .route("/api/auth/refresh", web::post().to(auth_controller::refresh))
+        .route("/api/auth/logout", web::post().to(auth_controller::logout))
+        .route("/api/auth/register", web::post().to(auth_controller::register))
+        .route("/api/auth/verify", web::get().to(auth_controller::verify_email))
+        .route("/api/auth/verify/resend", web::post().to(auth_controller::resend_verification))
+        .route("/api/auth/password/forgot", web::post().to(auth_controller::forgot_password))
+        .route("/api/auth/password/reset", web::post().to(auth_controller::reset_password))
+        .route("/api/auth/oauth/github/authorize", web::get().to(identity_controller::github_authorize))
+        .route("/api/auth/oauth/github/callback", web::post().to(identity_controller::github_callback))
+        .route("/.well-known/jwks.json", web::get().to(auth_controller::jwks));
 }
 
 pub fn configure_protected_auth_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
-            .route("/profile", web::get().to(auth_controller::get_profile))
+            .route("/profile", web::get().to(profile_controller::get_profile)) // 同 GET /api/me
+            .route("/password/change", web::post().to(auth_controller::change_password).wrap(RequireSession))
             .route("/roles", web::get().to(auth_controller::get_roles))
             .route("/permissions", web::get().to(auth_controller::get_permissions))
+            // scope 按注册顺序匹配，需在 /admin 之前注册
+            .service(
+                web::scope("/admin/roles")
+                    .wrap(RequirePermission::new("admin:access"))
+                    .route("", web::get().to(role_controller::get_roles))
+                    .route("", web::post().to(role_controller::create_role))
+                    .route("/{id}", web::get().to(role_controller::get_role_by_id))
+                    .route("/{id}", web::put().to(role_controller::update_role))
+                    .route("/{id}", web::delete().to(role_controller::delete_role))
+                    .route("/{id}/permissions", web::put().to(role_controller::set_role_permissions))
+            )
             .service(
+                web::scope("/admin/permissions")
+                    .wrap(RequirePermission::new("admin:access"))
+                    .route("", web::get().to(role_controller::get_permissions))
+                    .route("", web::post().to(role_controller::create_permission))
+                    .route("/{id}", web::put().to(role_controller::update_permission))
+                    .route("/{id}", web::delete().to(role_controller::delete_permission))
+            )
+            .service(
+                // 按当前权限而非令牌中的角色判断，移除管理员角色后立即失效
                 web::scope("/admin")
-                    .wrap(RequireRole::new("admin"))
+                    .wrap(RequirePermission::new("admin:access"))
+                    .route("/users", web::get().to(user_controller::get_users))
                     .route("/users", web::post().to(auth_controller::create_user))
+                    .route("/users/{id}", web::get().to(user_controller::get_user_by_id))
+                    .route("/users/{id}", web::put().to(user_controller::update_user))
+                    .route("/users/{id}", web::delete().to(user_controller::delete_user))
+                    .route("/users/{id}/roles", web::put().to(user_controller::set_user_roles))
+                    .route("/users/{id}/disable", web::post().to(user_controller::disable_user))
+                    .route("/users/{id}/enable", web::post().to(user_controller::enable_user))
+                    .route("/users/{id}/unlock", web::post().to(auth_controller::unlock_user))
+                    .route("/users/{id}/2fa/reset", web::post().to(auth_controller::reset_user_two_factor))
+                    .route("/users/{id}/sessions", web::get().to(session_controller::get_user_sessions))
+                    .route("/users/{id}/sessions", web::delete().to(session_controller::revoke_user_sessions))
             )
     );
+}
+
+// 需要 MySQL：L2E_TEST_DATABASE_URL=... cargo test -- --ignored
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App};
+    use actix_web_httpauth::middleware::HttpAuthentication;
+    use super::*;
+    use crate::test_support::{database_pool, grant_permission, insert_user, test_state};
+    use crate::utils::jwt::jwt_validator;
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn demoted_admin_loses_user_management() {
+        let state = web::Data::new(test_state(database_pool().await));
+        let user_id = insert_user(&state.pool).await;
+        let role_id = grant_permission(&state.pool, user_id, "admin:access").await;
+        // 令牌签发时仍是管理员，降级后令牌中的角色不会变化
+        let roles = ["admin".to_string()];
+        let perms = ["admin:access".to_string()];
+        let jti = uuid::Uuid::new_v4().to_string();
+        let token = state.jwt.create_token(&user_id.to_string(), &roles, &perms, &jti, "", false).unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .service(
+                    web::scope("/api")
+                        .wrap(HttpAuthentication::bearer(jwt_validator))
+                        .configure(configure_protected_auth_routes),
+                ),
+        )
+        .await;
+        let list_users = || {
+            test::TestRequest::get()
+                .uri("/api/auth/admin/users")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        let response = test::call_service(&app, list_users()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 与 set_user_roles 相同：改角色后使该用户的权限缓存失效
+        sqlx::query("DELETE FROM sys_user_role WHERE user_id = ? AND role_id = ?")
+            .bind(user_id)
+            .bind(role_id)
+            .execute(&state.pool)
+            .await
+            .unwrap();
+        state.permission_cache.invalidate_user(user_id);
+
+        let response = test::call_service(&app, list_users()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+use actix_web::web;
+use crate::controllers::{
+    identity_controller, personal_token_controller, profile_controller, session_controller, two_factor_controller,
+    wallet_controller,
+};
+use crate::middleware::auth::RequireSession;
+
+// 当前登录用户自己的资源，不接受个人访问令牌
+pub fn configure_me_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/me")
+            .wrap(RequireSession)
+            .route("", web::get().to(profile_controller::get_profile))
+            .route("", web::patch().to(profile_controller::update_profile))
+            .route("/wallets", web::get().to(wallet_controller::get_wallets))
+            .route("/wallets", web::post().to(wallet_controller::bind_wallet))
+            .route("/wallets/challenge", web::post().to(wallet_controller::create_challenge))
+            .route("/wallets/{id}/primary", web::put().to(wallet_controller::set_primary_wallet))
+            .route("/wallets/{id}", web::delete().to(wallet_controller::delete_wallet))
+            .route("/2fa", web::get().to(two_factor_controller::get_two_factor_status))
+            .route("/2fa/setup", web::post().to(two_factor_controller::setup_two_factor))
+            .route("/2fa/verify", web::post().to(two_factor_controller::verify_two_factor))
+            .route("/2fa/disable", web::post().to(two_factor_controller::disable_two_factor))
+            .route("/identities", web::get().to(identity_controller::get_identities))
+            .route("/identities/github/authorize", web::post().to(identity_controller::github_link_authorize))
+            .route("/identities/github", web::post().to(identity_controller::link_github))
+            .route("/identities/{provider}", web::delete().to(identity_controller::unlink_identity))
+            .route("/tokens", web::get().to(personal_token_controller::get_tokens))
+            .route("/tokens", web::post().to(personal_token_controller::create_token))
+            .route("/tokens/{id}", web::delete().to(personal_token_controller::revoke_token))
+            .route("/sessions", web::get().to(session_controller::get_sessions))
+            .route("/sessions/{id}", web::delete().to(session_controller::revoke_session))
+    );
+}
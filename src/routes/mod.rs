@@ -1,3 +1,5 @@
 pub mod task_routes;
 pub mod auth_routes;
-pub mod task_submission_routes;
\ No newline at end of file
+pub mod task_submission_routes;
+pub mod payout_routes;
+pub mod me_routes;
\ No newline at end of file
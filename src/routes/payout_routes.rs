@@ -0,0 +1,14 @@
+use actix_web::web;
+use crate::controllers::payout_controller;
+use crate::middleware::permission::RequirePermission;
+
+pub fn configure_payout_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/payouts")
+            .route("", web::get().to(payout_controller::get_payouts).wrap(RequirePermission::new("payout:read")))
+            .route("/{id}", web::get().to(payout_controller::get_payout_by_id).wrap(RequirePermission::new("payout:read")))
+            .route("/{id}/retry", web::post().to(payout_controller::retry_payout).wrap(RequirePermission::new("payout:manage")))
+            .route("/{id}/sync", web::post().to(payout_controller::sync_payout).wrap(RequirePermission::new("payout:manage")))
+    );
+    // 为提交创建发放的入口见 task_submission_routes: POST /submissions/{id}/payout
+}
@@ -1,5 +1,6 @@
 use actix_web::web;
-use crate::controllers::task_submission_controller;
+use crate::controllers::{payout_controller, task_submission_controller};
+use crate::middleware::permission::RequirePermission;
 
 pub fn configure_task_submission_routes(cfg: &mut web::ServiceConfig) {
     // cfg.service(
@@ -7,10 +8,17 @@ pub fn configure_task_submission_routes(cfg: &mut web::ServiceConfig) {
     //         .route("/tasks/{task_id}/submissions", web::get().to(task_submission_controller::get_submissions_by_task_id))
     //         .route("/submissions/{id}", web::get().to(task_submission_controller::get_submission_by_id))
     // );
-        cfg.service(
+    cfg.service(
         web::scope("/submissions")
+            .route("", web::post().to(task_submission_controller::create_submission))
+            .route("/{id}", web::put().to(task_submission_controller::update_submission))
             .route("/{id}", web::get().to(task_submission_controller::get_submission_by_id))
-            .route("/{id}/approve", web::post().to(task_submission_controller::approve_submission))
-            .route("/{id}/reject", web::post().to(task_submission_controller::reject_submission))
+            .route("/{id}/history", web::get().to(task_submission_controller::get_submission_history))
+            .route("/{id}/withdraw", web::post().to(task_submission_controller::withdraw_submission))
+            .route("/{id}/review", web::post().to(task_submission_controller::start_review).wrap(RequirePermission::new("submission:review")))
+            .route("/{id}/request-changes", web::post().to(task_submission_controller::request_changes).wrap(RequirePermission::new("submission:review")))
+            .route("/{id}/approve", web::post().to(task_submission_controller::approve_submission).wrap(RequirePermission::new("submission:review")))
+            .route("/{id}/reject", web::post().to(task_submission_controller::reject_submission).wrap(RequirePermission::new("submission:review")))
+            .route("/{id}/payout", web::post().to(payout_controller::create_submission_payout).wrap(RequirePermission::new("payout:manage")))
     );
 }
\ No newline at end of file
@@ -1,23 +1,127 @@
-use crate::models::user::{SysUser, LoginRequest, LoginResponse, UserResponse, CreateUserRequest};
-use crate::utils::jwt::JwtService;
-use sqlx::MySqlPool;
+use crate::error::AppError;
+use crate::mailer::EmailMessage;
+use crate::models::identity::OAuthCallbackRequest;
+use crate::models::session::ClientInfo;
+use crate::models::two_factor::{TwoFactorChallengeResponse, TwoFactorLoginRequest};
+use crate::models::wallet::WalletLoginRequest;
+use crate::models::user::{
+    SysUser, LoginRequest, LoginResponse, LoginOutcome, UserResponse, CreateUserRequest, RefreshTokenRequest, RegisterRequest,
+    ChangePasswordRequest, ResetPasswordRequest, UpdateProfileRequest, UserWithRoles, USER_STATUS_ACTIVE,
+    USER_STATUS_PENDING_VERIFICATION,
+};
+use crate::oauth::github::GITHUB_PROVIDER;
+use crate::oauth::OAuthProfile;
+use crate::services::identity_service::{IdentityService, OAuthStateCheck};
+use crate::services::login_throttle_service::LoginThrottleService;
+use crate::services::permission_service::PermissionService;
+use crate::services::session_service::SessionService;
+use crate::services::token_service::{RefreshTokenCheck, TokenService, UserTokenPurpose};
+use crate::services::two_factor_service::TwoFactorService;
+use crate::services::user_service::UserService;
+use crate::services::wallet_service::WalletService;
+use crate::state::AppState;
+use sqlx::Row;
 use bcrypt::{hash, verify, DEFAULT_COST};
-use actix_web::{web, HttpResponse, Result};
 use rand::{thread_rng, Rng};
 use rand::distributions::Alphanumeric;
 
-pub struct AuthService {
-    jwt_service: JwtService,
+// 自助注册用户默认分配的角色
+const DEFAULT_ROLE_KEY: &str = "user";
+
+const MIN_PASSWORD_LEN: usize = 8;
+
+// auth.two_factor.require_for_reviewers 针对的权限
+const REVIEW_PERMISSION: &str = "submission:review";
+
+// 用户不存在时也校验一次密码，使响应时间与密码错误时相同，避免据此枚举用户名；cost 需与 DEFAULT_COST 一致
+const DUMMY_PASSWORD_HASH: &str = "$2b$12$pvP7t53k7/qh008EYVzpoON1WIkvjKbU9XEukOYHQA0pZrqpOM4KO";
+
+pub struct AuthService;
+
+// 校验注册字段，按字段返回错误明细
+fn validate_registration(request: &RegisterRequest) -> Result<(), AppError> {
+    let mut field_errors = serde_json::Map::new();
+
+    if let Some(problem) = username_problem(request.username.trim()) {
+        field_errors.insert("username".to_string(), problem.into());
+    }
+    if !is_valid_email(request.email.trim()) {
+        field_errors.insert("email".to_string(), "must be a valid email address".into());
+    }
+    if let Some(problem) = password_problem(&request.password) {
+        field_errors.insert("password".to_string(), problem.into());
+    }
+
+    if !field_errors.is_empty() {
+        return Err(AppError::validation_with_details("Invalid registration", field_errors.into()));
+    }
+    Ok(())
 }
 
-impl AuthService {
-    pub fn new() -> Self {
-        AuthService {
-            jwt_service: JwtService::new(),
-        }
+// 注册和管理员修改用户名共用的用户名规则
+pub fn username_problem(username: &str) -> Option<&'static str> {
+    if username.len() < 3 || username.len() > 64 {
+        return Some("must be 3-64 characters");
+    }
+    if !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Some("may only contain letters, digits, '_' and '-'");
+    }
+    None
+}
+
+// 注册、修改和重置密码共用的密码规则
+fn password_problem(password: &str) -> Option<String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Some(format!("must be at least {} characters", MIN_PASSWORD_LEN));
     }
+    None
+}
+
+fn validate_new_password(password: &str) -> Result<(), AppError> {
+    match password_problem(password) {
+        Some(problem) => Err(AppError::validation_with_details(
+            "Invalid password",
+            serde_json::json!({ "new_password": problem }),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn display_name_problem(display_name: &str) -> Option<&'static str> {
+    if display_name.chars().count() > 64 {
+        return Some("must be at most 64 characters");
+    }
+    if display_name.chars().any(char::is_control) {
+        return Some("must not contain control characters");
+    }
+    None
+}
+
+fn avatar_url_problem(avatar_url: &str) -> Option<&'static str> {
+    if avatar_url.len() > 512 {
+        return Some("must be at most 512 characters");
+    }
+    if !(avatar_url.starts_with("https://") || avatar_url.starts_with("http://"))
+        || avatar_url.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Some("must be an http(s) URL");
+    }
+    None
+}
+
+// 只做基本格式校验，邮箱是否可用由验证邮件确认
+pub fn is_valid_email(email: &str) -> bool {
+    if email.len() > 255 || email.chars().any(char::is_whitespace) {
+        return false;
+    }
+    match email.split_once('@') {
+        Some((local, domain)) => !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.') && !domain.contains('@'),
+        None => false,
+    }
+}
 
-    fn generate_salt(&self) -> String {
+impl AuthService {
+    fn generate_salt() -> String {
         thread_rng()
             .sample_iter(&Alphanumeric)
             .take(16)
@@ -25,168 +129,821 @@ impl AuthService {
             .collect()
     }
 
-    pub async fn login(&self, pool: &MySqlPool, login_data: LoginRequest) -> Result<HttpResponse> {
+    // 生成盐值和密码hash，返回 (salt, hash)
+    fn hash_password(password: &str) -> Result<(String, String), AppError> {
+        let salt = Self::generate_salt();
+        let password_with_salt = format!("{}{}", password, salt);
+        let password_hash = hash(&password_with_salt, DEFAULT_COST)?;
+        Ok((salt, password_hash))
+    }
+
+    fn verify_password(password: &str, salt: &str, password_hash: &str) -> bool {
+        let password_with_salt = format!("{}{}", password, salt);
+        verify(&password_with_salt, password_hash).unwrap_or(false)
+    }
+
+    pub async fn login(
+        state: &AppState,
+        login_data: LoginRequest,
+        client_info: &ClientInfo,
+    ) -> Result<LoginOutcome, AppError> {
+        let client_ip = client_info.ip.as_deref();
+        let throttle = &state.settings.auth.login;
+        // 用户名不区分大小写，失败计数使用统一的形式
+        let throttle_key = login_data.username.trim().to_lowercase();
+        LoginThrottleService::check(&state.pool, &throttle_key, client_ip).await?;
+
         // 查找用户
         let user = sqlx::query_as::<_, SysUser>(
-            "SELECT id, username, password_hash, salt, status, created_at FROM sys_user WHERE username = ? AND status = 1"
+            "SELECT id, username, email, password_hash, salt, status, locked_until, created_at FROM sys_user WHERE username = ?"
         )
         .bind(&login_data.username)
-        .fetch_optional(pool)
-        .await;
-
-        match user {
-            Ok(Some(user)) => {
-                // 生成用于验证的密码hash
-                let password_with_salt = format!("{}{}", login_data.password, user.salt);
-                
-                if verify(&password_with_salt, &user.password_hash).unwrap_or(false) {
-                    // 获取用户角色
-                    let roles: Vec<String> = sqlx::query_scalar(
-                        "SELECT r.role_key FROM sys_role r 
-                         JOIN sys_user_role ur ON r.id = ur.role_id 
-                         WHERE ur.user_id = ? AND r.status = 1"
-                    )
-                    .bind(user.id)
-                    .fetch_all(pool)
-                    .await
-                    .unwrap_or_default();
-
-                    // 获取用户权限
-                    let permissions: Vec<String> = sqlx::query_scalar(
-                        "SELECT DISTINCT p.perm_key FROM sys_permission p
-                         JOIN sys_role_perm rp ON p.id = rp.perm_id
-                         JOIN sys_user_role ur ON rp.role_id = ur.role_id
-                         WHERE ur.user_id = ?"
-                    )
-                    .bind(user.id)
-                    .fetch_all(pool)
-                    .await
-                    .unwrap_or_default();
-
-                    let token = self.jwt_service.create_token(
-                        &user.id.to_string(), 
-                        &user.username
-                    ).unwrap();
-                    
-                    Ok(HttpResponse::Ok().json(LoginResponse {
-                        token,
-                        user: UserResponse {
-                            id: user.id,
-                            username: user.username,
-                            roles,
-                            permissions,
-                        },
-                    }))
-                } else {
-                    Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                        "error": "Invalid credentials"
-                    })))
-                }
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(user) = user else {
+            Self::verify_password(&login_data.password, "", DUMMY_PASSWORD_HASH);
+            LoginThrottleService::record_failure(&state.pool, throttle, &throttle_key, client_ip, None).await?;
+            return Err(AppError::Unauthorized("Invalid credentials".to_string()));
+        };
+
+        LoginThrottleService::check_user_lock(user.locked_until)?;
+
+        if !Self::verify_password(&login_data.password, &user.salt, &user.password_hash) {
+            LoginThrottleService::record_failure(&state.pool, throttle, &throttle_key, client_ip, Some(user.id)).await?;
+            return Err(AppError::Unauthorized("Invalid credentials".to_string()));
+        }
+
+        // 密码正确后才提示未验证邮箱，避免泄露账号状态
+        match user.status {
+            USER_STATUS_ACTIVE => {}
+            USER_STATUS_PENDING_VERIFICATION => {
+                return Err(AppError::Forbidden("Email address has not been verified".to_string()));
+            }
+            _ => return Err(AppError::Unauthorized("Invalid credentials".to_string())),
+        }
+
+        // 已启用两步验证：失败计数保留到验证码通过后再清除
+        if let Some(challenge) = Self::two_factor_challenge(state, user.id).await? {
+            return Ok(LoginOutcome::TwoFactorRequired(challenge));
+        }
+
+        LoginThrottleService::record_success(&state.pool, &throttle_key, user.id).await?;
+
+        // 新登录开启一个新的 refresh token family
+        let family_id = uuid::Uuid::new_v4().to_string();
+        let response = Self::issue_tokens(state, user.id, user.username, &family_id, client_info).await?;
+        Ok(LoginOutcome::Tokens(response))
+    }
+
+    // 已启用两步验证的用户返回短期有效的挑战令牌，需继续调用 login_two_factor
+    async fn two_factor_challenge(
+        state: &AppState,
+        user_id: i64,
+    ) -> Result<Option<TwoFactorChallengeResponse>, AppError> {
+        if !TwoFactorService::is_enabled(&state.pool, user_id).await? {
+            return Ok(None);
+        }
+
+        let ttl_minutes = state.settings.auth.two_factor.challenge_ttl_minutes;
+        let challenge_token = TokenService::issue_user_token(
+            &state.pool,
+            user_id,
+            UserTokenPurpose::TwoFactorLogin,
+            chrono::Duration::minutes(ttl_minutes),
+        )
+        .await?;
+
+        Ok(Some(TwoFactorChallengeResponse {
+            two_factor_required: true,
+            challenge_token,
+            expires_in: ttl_minutes * 60,
+        }))
+    }
+
+    // GitHub 登录：已绑定的账号直接登录，否则按注册规则创建新用户
+    pub async fn login_with_github(
+        state: &AppState,
+        request: OAuthCallbackRequest,
+        client_info: &ClientInfo,
+    ) -> Result<LoginOutcome, AppError> {
+        let github = state.github_client()?;
+        match IdentityService::consume_state(&state.pool, GITHUB_PROVIDER, &request.state).await? {
+            OAuthStateCheck::Login => {}
+            OAuthStateCheck::Link { .. } => {
+                return Err(AppError::validation("This authorization was started for account linking"));
+            }
+            OAuthStateCheck::Invalid => {
+                return Err(AppError::Unauthorized("Invalid or expired OAuth state".to_string()));
+            }
+        }
+
+        let profile = github.authenticate(&request.code).await?;
+
+        let user_id = match IdentityService::find_user(&state.pool, GITHUB_PROVIDER, &profile).await? {
+            Some(user_id) => user_id,
+            None => Self::create_oauth_user(state, GITHUB_PROVIDER, &profile).await?,
+        };
+
+        let user = sqlx::query_as::<_, SysUser>(
+            "SELECT id, username, email, password_hash, salt, status, locked_until, created_at FROM sys_user WHERE id = ? AND status = ?"
+        )
+        .bind(user_id)
+        .bind(USER_STATUS_ACTIVE)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(user) = user else {
+            return Err(AppError::Unauthorized("Account is disabled".to_string()));
+        };
+        LoginThrottleService::check_user_lock(user.locked_until)?;
+
+        if let Some(challenge) = Self::two_factor_challenge(state, user.id).await? {
+            return Ok(LoginOutcome::TwoFactorRequired(challenge));
+        }
+
+        let family_id = uuid::Uuid::new_v4().to_string();
+        let response = Self::issue_tokens(state, user.id, user.username, &family_id, client_info).await?;
+        Ok(LoginOutcome::Tokens(response))
+    }
+
+    // 为第三方账号创建本地用户，邮箱取提供方已验证的主邮箱
+    async fn create_oauth_user(state: &AppState, provider: &str, profile: &OAuthProfile) -> Result<i64, AppError> {
+        if !state.settings.auth.allow_registration {
+            return Err(AppError::Forbidden("Registration is disabled".to_string()));
+        }
+
+        // 邮箱已属于本地账号时不自动合并，需用户登录后自行绑定
+        if let Some(email) = &profile.email {
+            let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE email = ?")
+                .bind(email)
+                .fetch_one(&state.pool)
+                .await?;
+
+            if taken > 0 {
+                return Err(AppError::Conflict(format!(
+                    "An account with this email already exists, log in and link your {} account from your profile",
+                    provider
+                )));
             }
-            Ok(None) => Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": "Invalid credentials"
-            }))),
-            Err(_) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Database error"
-            }))),
         }
+
+        let username = Self::available_username(state, &profile.login).await?;
+
+        let mut tx = state.pool.begin().await?;
+        let user_id = Self::insert_external_user(&mut tx, &username, profile.email.as_deref()).await?;
+        IdentityService::insert(&mut *tx, user_id, provider, profile).await?;
+        tx.commit().await?;
+
+        Ok(user_id)
     }
 
-    pub async fn create_user(&self, pool: &MySqlPool, user_data: CreateUserRequest) -> Result<HttpResponse> {
+    // 钱包登录：地址需已绑定到账号，未绑定时按注册规则创建新用户
+    pub async fn login_with_wallet(
+        state: &AppState,
+        request: WalletLoginRequest,
+        client_info: &ClientInfo,
+    ) -> Result<LoginOutcome, AppError> {
+        if !state.settings.auth.wallet_login {
+            return Err(AppError::Forbidden("Wallet login is disabled".to_string()));
+        }
+
+        let address = WalletService::verify_login(&state.pool, &request).await?;
+
+        let user_id = match WalletService::find_user_by_address(&state.pool, &address).await? {
+            Some(user_id) => user_id,
+            None => Self::create_wallet_user(state, &address).await?,
+        };
+
+        let user = sqlx::query_as::<_, SysUser>(
+            "SELECT id, username, email, password_hash, salt, status, locked_until, created_at FROM sys_user WHERE id = ? AND status = ?"
+        )
+        .bind(user_id)
+        .bind(USER_STATUS_ACTIVE)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(user) = user else {
+            return Err(AppError::Unauthorized("Account is disabled".to_string()));
+        };
+        LoginThrottleService::check_user_lock(user.locked_until)?;
+
+        if let Some(challenge) = Self::two_factor_challenge(state, user.id).await? {
+            return Ok(LoginOutcome::TwoFactorRequired(challenge));
+        }
+
+        let family_id = uuid::Uuid::new_v4().to_string();
+        let response = Self::issue_tokens(state, user.id, user.username, &family_id, client_info).await?;
+        Ok(LoginOutcome::Tokens(response))
+    }
+
+    // 首次使用钱包登录时创建用户，该地址同时成为默认收款地址
+    async fn create_wallet_user(state: &AppState, address: &str) -> Result<i64, AppError> {
+        if !state.settings.auth.allow_registration {
+            return Err(AppError::Forbidden("Registration is disabled".to_string()));
+        }
+
+        // 0x 之后取 8 位作为默认用户名
+        let username = Self::available_username(state, &format!("sui-{}", &address[2..10])).await?;
+
+        let mut tx = state.pool.begin().await?;
+        let user_id = Self::insert_external_user(&mut tx, &username, None).await?;
+
+        sqlx::query("INSERT INTO user_wallet (user_id, address, is_primary) VALUES (?, ?, 1)")
+            .bind(user_id)
+            .bind(address)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(user_id)
+    }
+
+    // 第三方登录创建的用户：不设置本地密码，分配默认角色
+    async fn insert_external_user(
+        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        username: &str,
+        verified_email: Option<&str>,
+    ) -> Result<i64, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO sys_user (username, email, email_verified_at, password_hash, salt, status) VALUES (?, ?, ?, '', '', ?)"
+        )
+        .bind(username)
+        .bind(verified_email)
+        .bind(verified_email.map(|_| chrono::Utc::now()))
+        .bind(USER_STATUS_ACTIVE)
+        .execute(&mut **tx)
+        .await?;
+
+        let user_id = result.last_insert_id() as i64;
+
+        let role = sqlx::query("INSERT INTO sys_user_role (user_id, role_id) SELECT ?, id FROM sys_role WHERE role_key = ?")
+            .bind(user_id)
+            .bind(DEFAULT_ROLE_KEY)
+            .execute(&mut **tx)
+            .await?;
+
+        if role.rows_affected() == 0 {
+            return Err(AppError::Internal(format!("Default role '{}' does not exist", DEFAULT_ROLE_KEY)));
+        }
+        Ok(user_id)
+    }
+
+    // 优先使用第三方用户名，被占用时追加后缀
+    async fn available_username(state: &AppState, login: &str) -> Result<String, AppError> {
+        let base: String = login
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .take(40)
+            .collect();
+        let base = if base.len() < 3 { format!("user-{}", base) } else { base };
+
+        let mut candidates = vec![base.clone()];
+        candidates.extend((1..=5).map(|n| format!("{}-{}", base, n)));
+        candidates.push(format!("{}-{}", base, Self::generate_salt().to_lowercase()));
+
+        for candidate in candidates {
+            let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE username = ?")
+                .bind(&candidate)
+                .fetch_one(&state.pool)
+                .await?;
+            if taken == 0 {
+                return Ok(candidate);
+            }
+        }
+        Err(AppError::Conflict("Could not find an available username".to_string()))
+    }
+
+    // 两步登录的第二步：提交挑战令牌和 TOTP 验证码（或恢复码）
+    pub async fn login_two_factor(
+        state: &AppState,
+        request: TwoFactorLoginRequest,
+        client_info: &ClientInfo,
+    ) -> Result<LoginResponse, AppError> {
+        let client_ip = client_info.ip.as_deref();
+        let user_id = TokenService::find_user_token(&state.pool, UserTokenPurpose::TwoFactorLogin, &request.challenge_token).await?;
+
+        let Some(user_id) = user_id else {
+            return Err(AppError::Unauthorized("Invalid or expired two-factor challenge".to_string()));
+        };
+
+        let user = sqlx::query_as::<_, SysUser>(
+            "SELECT id, username, email, password_hash, salt, status, locked_until, created_at FROM sys_user WHERE id = ? AND status = ?"
+        )
+        .bind(user_id)
+        .bind(USER_STATUS_ACTIVE)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(user) = user else {
+            return Err(AppError::Unauthorized("Invalid or expired two-factor challenge".to_string()));
+        };
+
+        // 验证码错误与密码错误共用失败计数和锁定
+        let throttle_key = user.username.to_lowercase();
+        LoginThrottleService::check(&state.pool, &throttle_key, client_ip).await?;
+        LoginThrottleService::check_user_lock(user.locked_until)?;
+
+        if !TwoFactorService::verify_code(&state.pool, user.id, &request.code).await? {
+            LoginThrottleService::record_failure(&state.pool, &state.settings.auth.login, &throttle_key, client_ip, Some(user.id)).await?;
+            return Err(AppError::Unauthorized("Invalid verification code".to_string()));
+        }
+
+        // 并发提交同一挑战时只有一个请求成功
+        if TokenService::consume_user_token(&state.pool, UserTokenPurpose::TwoFactorLogin, &request.challenge_token).await?.is_none() {
+            return Err(AppError::Unauthorized("Invalid or expired two-factor challenge".to_string()));
+        }
+
+        LoginThrottleService::record_success(&state.pool, &throttle_key, user.id).await?;
+
+        let family_id = uuid::Uuid::new_v4().to_string();
+        Self::issue_tokens(state, user.id, user.username, &family_id, client_info).await
+    }
+
+    // 签发 access token 和同 family 下的新 refresh token
+    async fn issue_tokens(
+        state: &AppState,
+        user_id: i64,
+        username: String,
+        family_id: &str,
+        client_info: &ClientInfo,
+    ) -> Result<LoginResponse, AppError> {
+        // 获取用户角色和权限，写入 token 声明
+        let mut roles = PermissionService::get_user_roles(&state.pool, user_id).await?;
+        let mut permissions = PermissionService::get_user_permissions(&state.pool, user_id).await?;
+
+        // 策略要求审核人员启用两步验证：未启用前签发不带角色和权限的令牌，仅能完成 2FA 设置
+        let two_factor_setup_required = state.settings.auth.two_factor.require_for_reviewers
+            && permissions.iter().any(|p| p == REVIEW_PERMISSION)
+            && !TwoFactorService::is_enabled(&state.pool, user_id).await?;
+        if two_factor_setup_required {
+            roles.clear();
+            permissions.clear();
+        }
+
+        // 每个 refresh token family 对应一条会话记录
+        SessionService::record(&state.pool, user_id, family_id, client_info).await?;
+
+        let jti = uuid::Uuid::new_v4().to_string();
+        let token = state.jwt.create_token(
+            &user_id.to_string(),
+            &roles,
+            &permissions,
+            &jti,
+            family_id,
+            two_factor_setup_required,
+        )?;
+        let refresh_token = TokenService::issue_refresh_token(&state.pool, &state.settings.jwt, user_id, family_id, &jti).await?;
+
+        Ok(LoginResponse {
+            token,
+            refresh_token,
+            expires_in: state.settings.jwt.access_ttl_minutes * 60,
+            user: UserResponse {
+                id: user_id,
+                username,
+                roles,
+                permissions,
+            },
+            two_factor_setup_required,
+        })
+    }
+
+    // 使用 refresh token 换取新的令牌对，旧 refresh token 随即失效
+    pub async fn refresh(
+        state: &AppState,
+        request: RefreshTokenRequest,
+        client_info: &ClientInfo,
+    ) -> Result<LoginResponse, AppError> {
+        let (user_id, family_id) = match TokenService::consume_refresh_token(&state.pool, &state.settings.jwt, &request.refresh_token).await? {
+            RefreshTokenCheck::Valid { user_id, family_id } => (user_id, family_id),
+            RefreshTokenCheck::Invalid => {
+                return Err(AppError::Unauthorized("Invalid refresh token".to_string()));
+            }
+            RefreshTokenCheck::Reused => {
+                return Err(AppError::Unauthorized(
+                    "Refresh token reuse detected, please log in again".to_string(),
+                ));
+            }
+        };
+
+        // 被禁用的用户不能续期
+        let username: Option<String> = sqlx::query_scalar(
+            "SELECT username FROM sys_user WHERE id = ? AND status = 1"
+        )
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(username) = username else {
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()));
+        };
+
+        Self::issue_tokens(state, user_id, username, &family_id, client_info).await
+    }
+
+    // 登出：吊销 refresh token 所在 family 及其签发的 access token
+    pub async fn logout(state: &AppState, request: RefreshTokenRequest) -> Result<(), AppError> {
+        TokenService::revoke_by_refresh_token(&state.pool, &state.settings.jwt, &request.refresh_token).await?;
+        Ok(())
+    }
+
+    pub async fn create_user(state: &AppState, user_data: CreateUserRequest) -> Result<i64, AppError> {
         // 检查用户是否已存在
         let existing_user = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM sys_user WHERE username = ?"
         )
         .bind(&user_data.username)
-        .fetch_one(pool)
-        .await;
-
-        if let Ok(count) = existing_user {
-            if count > 0 {
-                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": "User already exists"
-                })));
-            }
+        .fetch_one(&state.pool)
+        .await?;
+
+        if existing_user > 0 {
+            return Err(AppError::Conflict("User already exists".to_string()));
         }
 
-        // 生成盐值和密码hash
-        let salt = self.generate_salt();
-        let password_with_salt = format!("{}{}", user_data.password, salt);
-        let password_hash = match hash(&password_with_salt, DEFAULT_COST) {
-            Ok(hash) => hash,
-            Err(_) => return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to hash password"
-            }))),
-        };
+        let (salt, password_hash) = Self::hash_password(&user_data.password)?;
 
-        // 开始事务
-        let mut tx = pool.begin().await.unwrap();
+        // 开始事务，任何一步失败时事务在 drop 时自动回滚
+        let mut tx = state.pool.begin().await?;
 
         // 创建用户
-        let user_result = sqlx::query(
+        let result = sqlx::query(
             "INSERT INTO sys_user (username, password_hash, salt) VALUES (?, ?, ?)"
         )
         .bind(&user_data.username)
         .bind(&password_hash)
         .bind(&salt)
         .execute(&mut *tx)
-        .await;
-
-        match user_result {
-            Ok(result) => {
-                let user_id = result.last_insert_id() as i64;
-
-                // 分配角色
-                for role_id in user_data.role_ids {
-                    sqlx::query("INSERT INTO sys_user_role (user_id, role_id) VALUES (?, ?)")
-                        .bind(user_id)
-                        .bind(role_id)
-                        .execute(&mut *tx)
-                        .await
-                        .unwrap();
-                }
+        .await?;
+
+        let user_id = result.last_insert_id() as i64;
 
-                tx.commit().await.unwrap();
+        // 分配角色，角色 ID 在同一事务中校验
+        UserService::assign_roles(&mut tx, user_id, &user_data.role_ids).await?;
 
-                Ok(HttpResponse::Created().json(serde_json::json!({
-                    "message": "User created successfully",
-                    "user_id": user_id
-                })))
+        tx.commit().await?;
+
+        Ok(user_id)
+    }
+
+    // 自助注册：账号在邮箱验证前处于待验证状态，无法登录
+    pub async fn register(
+        state: &AppState,
+        request: RegisterRequest,
+    ) -> Result<i64, AppError> {
+        if !state.settings.auth.allow_registration {
+            return Err(AppError::Forbidden("Registration is disabled".to_string()));
+        }
+        validate_registration(&request)?;
+
+        let username = request.username.trim().to_string();
+        let email = request.email.trim().to_lowercase();
+
+        let taken: Option<String> = sqlx::query_scalar(
+            "SELECT IF(username = ?, 'username', 'email') FROM sys_user WHERE username = ? OR email = ? LIMIT 1"
+        )
+        .bind(&username)
+        .bind(&username)
+        .bind(&email)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        if let Some(field) = taken {
+            return Err(AppError::Conflict(format!("The {} is already registered", field)));
+        }
+
+        let (salt, password_hash) = Self::hash_password(&request.password)?;
+
+        let mut tx = state.pool.begin().await?;
+
+        let result = sqlx::query(
+            "INSERT INTO sys_user (username, email, password_hash, salt, status) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(&username)
+        .bind(&email)
+        .bind(&password_hash)
+        .bind(&salt)
+        .bind(USER_STATUS_PENDING_VERIFICATION)
+        .execute(&mut *tx)
+        .await?;
+
+        let user_id = result.last_insert_id() as i64;
+
+        let role = sqlx::query("INSERT INTO sys_user_role (user_id, role_id) SELECT ?, id FROM sys_role WHERE role_key = ?")
+            .bind(user_id)
+            .bind(DEFAULT_ROLE_KEY)
+            .execute(&mut *tx)
+            .await?;
+
+        if role.rows_affected() == 0 {
+            return Err(AppError::Internal(format!("Default role '{}' does not exist", DEFAULT_ROLE_KEY)));
+        }
+
+        tx.commit().await?;
+
+        Self::send_verification_email(state, user_id, &email).await?;
+        Ok(user_id)
+    }
+
+    async fn send_verification_email(
+        state: &AppState,
+        user_id: i64,
+        email: &str,
+    ) -> Result<(), AppError> {
+        let ttl = chrono::Duration::hours(state.settings.auth.verification_ttl_hours);
+        let token = TokenService::issue_user_token(&state.pool, user_id, UserTokenPurpose::EmailVerification, ttl).await?;
+        let link = format!("{}/api/auth/verify?token={}", state.public_url(), token);
+
+        state.mailer
+            .send(EmailMessage {
+                to: email.to_string(),
+                subject: "Verify your Learn2Earn account".to_string(),
+                body: format!(
+                    "Welcome to Learn2Earn!\n\nOpen the link below to verify your email address:\n{}\n\nThe link expires in {} hours.",
+                    link, state.settings.auth.verification_ttl_hours
+                ),
+            })
+            .await?;
+        Ok(())
+    }
+
+    // 邮箱验证通过后激活账号
+    pub async fn verify_email(state: &AppState, token: &str) -> Result<(), AppError> {
+        let user_id = TokenService::consume_user_token(&state.pool, UserTokenPurpose::EmailVerification, token).await?;
+
+        let Some(user_id) = user_id else {
+            return Err(AppError::validation("Invalid or expired verification token"));
+        };
+
+        // 已激活用户修改邮箱后同样通过此链接验证新邮箱
+        sqlx::query(
+            "UPDATE sys_user SET status = IF(status = ?, ?, status), email_verified_at = CURRENT_TIMESTAMP
+             WHERE id = ? AND status IN (?, ?) AND email IS NOT NULL"
+        )
+        .bind(USER_STATUS_PENDING_VERIFICATION)
+        .bind(USER_STATUS_ACTIVE)
+        .bind(user_id)
+        .bind(USER_STATUS_PENDING_VERIFICATION)
+        .bind(USER_STATUS_ACTIVE)
+        .execute(&state.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // 重新发送验证邮件；无论邮箱是否存在都返回成功，避免被用来探测账号
+    pub async fn resend_verification(
+        state: &AppState,
+        email: &str,
+    ) -> Result<(), AppError> {
+        let email = email.trim().to_lowercase();
+        let user_id: Option<i64> = sqlx::query_scalar("SELECT id FROM sys_user WHERE email = ? AND status = ?")
+            .bind(&email)
+            .bind(USER_STATUS_PENDING_VERIFICATION)
+            .fetch_optional(&state.pool)
+            .await?;
+
+        if let Some(user_id) = user_id {
+            Self::send_verification_email(state, user_id, &email).await?;
+        }
+        Ok(())
+    }
+
+    // 修改当前用户的资料；新邮箱需通过验证邮件确认，旧的验证链接随即失效
+    pub async fn update_profile(
+        state: &AppState,
+        user_id: i64,
+        request: UpdateProfileRequest,
+    ) -> Result<UserWithRoles, AppError> {
+        let mut field_errors = serde_json::Map::new();
+
+        let display_name = request.display_name.as_deref().map(str::trim);
+        if let Some(display_name) = display_name
+            && let Some(problem) = display_name_problem(display_name)
+        {
+            field_errors.insert("display_name".to_string(), problem.into());
+        }
+        let avatar_url = request.avatar_url.as_deref().map(str::trim);
+        if let Some(avatar_url) = avatar_url
+            && !avatar_url.is_empty()
+            && let Some(problem) = avatar_url_problem(avatar_url)
+        {
+            field_errors.insert("avatar_url".to_string(), problem.into());
+        }
+        let email = request.email.as_deref().map(|email| email.trim().to_lowercase());
+        if let Some(email) = &email
+            && !email.is_empty()
+            && !is_valid_email(email)
+        {
+            field_errors.insert("email".to_string(), "must be a valid email address".into());
+        }
+        if !field_errors.is_empty() {
+            return Err(AppError::validation_with_details("Invalid profile", field_errors.into()));
+        }
+
+        let row = sqlx::query(
+            "SELECT u.email, u.password_hash <> '' AS has_password,
+                    (SELECT COUNT(*) FROM user_identity i WHERE i.user_id = u.id)
+                      + (SELECT COUNT(*) FROM user_wallet w WHERE w.user_id = u.id) AS methods
+             FROM sys_user u WHERE u.id = ?"
+        )
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+        let current_email: Option<String> = row.get("email");
+
+        let new_email = match email {
+            Some(email) if current_email.as_deref() != Some(email.as_str()) => {
+                let email = (!email.is_empty()).then_some(email);
+                match &email {
+                    Some(email) => {
+                        let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE email = ? AND id <> ?")
+                            .bind(email)
+                            .bind(user_id)
+                            .fetch_one(&state.pool)
+                            .await?;
+                        if taken > 0 {
+                            return Err(AppError::Conflict("The email is already registered".to_string()));
+                        }
+                    }
+                    // 没有密码和其他登录方式时邮箱是找回账号的唯一途径
+                    None if !row.get::<bool, _>("has_password") && row.get::<i64, _>("methods") == 0 => {
+                        return Err(AppError::validation("Cannot remove the email of an account without another sign-in method"));
+                    }
+                    None => {}
+                }
+                Some(email)
             }
-            Err(_) => {
-                tx.rollback().await.unwrap();
-                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": "Failed to create user"
-                })))
+            _ => None,
+        };
+
+        if let Some(display_name) = display_name {
+            sqlx::query("UPDATE sys_user SET display_name = ? WHERE id = ?")
+                .bind((!display_name.is_empty()).then_some(display_name))
+                .bind(user_id)
+                .execute(&state.pool)
+                .await?;
+        }
+        if let Some(avatar_url) = avatar_url {
+            sqlx::query("UPDATE sys_user SET avatar_url = ? WHERE id = ?")
+                .bind((!avatar_url.is_empty()).then_some(avatar_url))
+                .bind(user_id)
+                .execute(&state.pool)
+                .await?;
+        }
+        if let Some(email) = new_email {
+            sqlx::query("UPDATE sys_user SET email = ?, email_verified_at = NULL WHERE id = ?")
+                .bind(&email)
+                .bind(user_id)
+                .execute(&state.pool)
+                .await?;
+
+            TokenService::invalidate_user_tokens(&state.pool, user_id, UserTokenPurpose::EmailVerification).await?;
+            if let Some(email) = &email {
+                Self::send_verification_email(state, user_id, email).await?;
             }
         }
+
+        UserService::get_profile(&state.pool, user_id).await
+    }
+
+    // 修改密码：校验当前密码，吊销所有已有会话后为当前用户签发新的令牌对
+    pub async fn change_password(
+        state: &AppState,
+        user_id: i64,
+        request: ChangePasswordRequest,
+        client_info: &ClientInfo,
+    ) -> Result<LoginResponse, AppError> {
+        let user = sqlx::query_as::<_, SysUser>(
+            "SELECT id, username, email, password_hash, salt, status, locked_until, created_at FROM sys_user WHERE id = ? AND status = ?"
+        )
+        .bind(user_id)
+        .bind(USER_STATUS_ACTIVE)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(user) = user else {
+            return Err(AppError::Unauthorized("Authentication required".to_string()));
+        };
+
+        if !Self::verify_password(&request.current_password, &user.salt, &user.password_hash) {
+            return Err(AppError::validation_with_details(
+                "Current password is incorrect",
+                serde_json::json!({ "current_password": "is incorrect" }),
+            ));
+        }
+        validate_new_password(&request.new_password)?;
+
+        Self::set_password(state, user.id, &request.new_password).await?;
+
+        let family_id = uuid::Uuid::new_v4().to_string();
+        Self::issue_tokens(state, user.id, user.username, &family_id, client_info).await
+    }
+
+    // 忘记密码：向已激活账号的邮箱发送重置链接；邮箱不存在时同样返回成功，避免被用来探测账号
+    pub async fn forgot_password(
+        state: &AppState,
+        email: &str,
+    ) -> Result<(), AppError> {
+        let email = email.trim().to_lowercase();
+        let user_id: Option<i64> = sqlx::query_scalar("SELECT id FROM sys_user WHERE email = ? AND status = ?")
+            .bind(&email)
+            .bind(USER_STATUS_ACTIVE)
+            .fetch_optional(&state.pool)
+            .await?;
+
+        let Some(user_id) = user_id else {
+            return Ok(());
+        };
+
+        let ttl_minutes = state.settings.auth.password_reset_ttl_minutes;
+        let token = TokenService::issue_user_token(
+            &state.pool,
+            user_id,
+            UserTokenPurpose::PasswordReset,
+            chrono::Duration::minutes(ttl_minutes),
+        )
+        .await?;
+
+        state.mailer
+            .send(EmailMessage {
+                to: email,
+                subject: "Reset your Learn2Earn password".to_string(),
+                body: format!(
+                    "We received a request to reset your password.\n\nUse this token with POST {}/api/auth/password/reset:\n{}\n\nThe token expires in {} minutes. If you did not request a reset, you can ignore this email.",
+                    state.public_url(), token, ttl_minutes
+                ),
+            })
+            .await?;
+        Ok(())
+    }
+
+    // 使用一次性重置令牌设置新密码，所有已有会话随即失效
+    pub async fn reset_password(state: &AppState, request: ResetPasswordRequest) -> Result<(), AppError> {
+        validate_new_password(&request.new_password)?;
+
+        let user_id = TokenService::consume_user_token(&state.pool, UserTokenPurpose::PasswordReset, &request.token).await?;
+
+        let Some(user_id) = user_id else {
+            return Err(AppError::validation("Invalid or expired reset token"));
+        };
+
+        Self::set_password(state, user_id, &request.new_password).await
+    }
+
+    // 更新密码并吊销该用户的所有会话和未使用的重置令牌
+    async fn set_password(state: &AppState, user_id: i64, password: &str) -> Result<(), AppError> {
+        let (salt, password_hash) = Self::hash_password(password)?;
+
+        sqlx::query("UPDATE sys_user SET password_hash = ?, salt = ? WHERE id = ?")
+            .bind(&password_hash)
+            .bind(&salt)
+            .bind(user_id)
+            .execute(&state.pool)
+            .await?;
+
+        TokenService::revoke_user_sessions(&state.pool, &state.settings.jwt, user_id).await?;
+        TokenService::invalidate_user_tokens(&state.pool, user_id, UserTokenPurpose::PasswordReset).await?;
+        Ok(())
     }
 
-    // 初始化默认用户密码（用于启动时重建demo用户密码）
-    pub async fn init_default_users(&self, pool: &MySqlPool) -> Result<(), sqlx::Error> {
+    // 初始化默认用户密码（仅在 auth.reset_default_passwords 开启时于启动时调用）
+    pub async fn init_default_users(state: &AppState) -> Result<(), AppError> {
         // 为admin用户设置密码 admin123
-        let admin_salt = self.generate_salt();
-        let admin_password = format!("{}{}", "admin123", admin_salt);
-        let admin_hash = hash(&admin_password, DEFAULT_COST).unwrap();
-        
+        let (admin_salt, admin_hash) = Self::hash_password("admin123")?;
+
         sqlx::query("UPDATE sys_user SET password_hash = ?, salt = ? WHERE username = 'admin'")
             .bind(&admin_hash)
             .bind(&admin_salt)
-            .execute(pool)
+            .execute(&state.pool)
             .await?;
 
         // 为demo用户设置密码 user123
-        let demo_salt = self.generate_salt();
-        let demo_password = format!("{}{}", "user123", demo_salt);
-        let demo_hash = hash(&demo_password, DEFAULT_COST).unwrap();
-        
+        let (demo_salt, demo_hash) = Self::hash_password("user123")?;
+
         sqlx::query("UPDATE sys_user SET password_hash = ?, salt = ? WHERE username = 'demo'")
             .bind(&demo_hash)
             .bind(&demo_salt)
-            .execute(pool)
+            .execute(&state.pool)
             .await?;
 
         println!("Default users initialized: admin/admin123, demo/user123");
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dummy_hash_matches_default_cost() {
+        let parts: Vec<&str> = DUMMY_PASSWORD_HASH.split('$').collect();
+        assert_eq!(parts[2].parse::<u32>().unwrap(), DEFAULT_COST);
+        assert_eq!(parts[3].len(), 53);
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::{Duration, Utc};
+use sqlx::{MySqlPool, Row};
+use crate::error::AppError;
+use crate::models::identity::UserIdentity;
+use crate::oauth::OAuthProfile;
+use crate::utils::token::{generate_token, hash_token};
+
+// 从跳转授权到回调的最长时间
+const STATE_TTL_MINUTES: i64 = 10;
+
+// 授权 state 的用途
+pub enum OAuthStateCheck {
+    Login,
+    Link { user_id: i64 },
+    Invalid,
+}
+
+pub struct IdentityService;
+
+impl IdentityService {
+    // 生成一次性 state；user_id 不为空表示为该用户绑定第三方账号
+    pub async fn create_state(pool: &MySqlPool, provider: &str, user_id: Option<i64>) -> Result<String, AppError> {
+        let state = generate_token(32);
+
+        sqlx::query("INSERT INTO oauth_state (provider, state_hash, user_id, expires_at) VALUES (?, ?, ?, ?)")
+            .bind(provider)
+            .bind(hash_token(&state))
+            .bind(user_id)
+            .bind(Utc::now() + Duration::minutes(STATE_TTL_MINUTES))
+            .execute(pool)
+            .await?;
+
+        // 顺带清理过期的 state
+        sqlx::query("DELETE FROM oauth_state WHERE expires_at < CURRENT_TIMESTAMP")
+            .execute(pool)
+            .await?;
+
+        Ok(state)
+    }
+
+    // 消费 state，每个只能使用一次
+    pub async fn consume_state(pool: &MySqlPool, provider: &str, state: &str) -> Result<OAuthStateCheck, AppError> {
+        let row = sqlx::query(
+            "SELECT id, user_id FROM oauth_state WHERE state_hash = ? AND provider = ? AND expires_at > CURRENT_TIMESTAMP"
+        )
+        .bind(hash_token(state))
+        .bind(provider)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(OAuthStateCheck::Invalid);
+        };
+
+        let result = sqlx::query("DELETE FROM oauth_state WHERE id = ?")
+            .bind(row.get::<i64, _>("id"))
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(OAuthStateCheck::Invalid);
+        }
+        Ok(match row.get::<Option<i64>, _>("user_id") {
+            Some(user_id) => OAuthStateCheck::Link { user_id },
+            None => OAuthStateCheck::Login,
+        })
+    }
+
+    // 查找已绑定的本地用户，同时更新第三方账号的用户名和邮箱
+    pub async fn find_user(pool: &MySqlPool, provider: &str, profile: &OAuthProfile) -> Result<Option<i64>, AppError> {
+        let user_id: Option<i64> = sqlx::query_scalar(
+            "SELECT user_id FROM user_identity WHERE provider = ? AND provider_user_id = ?"
+        )
+        .bind(provider)
+        .bind(&profile.provider_user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        if user_id.is_some() {
+            sqlx::query(
+                "UPDATE user_identity SET provider_login = ?, email = ? WHERE provider = ? AND provider_user_id = ?"
+            )
+            .bind(&profile.login)
+            .bind(&profile.email)
+            .bind(provider)
+            .bind(&profile.provider_user_id)
+            .execute(pool)
+            .await?;
+        }
+        Ok(user_id)
+    }
+
+    // 为已登录用户绑定第三方账号
+    pub async fn link(pool: &MySqlPool, user_id: i64, provider: &str, profile: &OAuthProfile) -> Result<UserIdentity, AppError> {
+        let owner: Option<i64> = sqlx::query_scalar(
+            "SELECT user_id FROM user_identity WHERE provider = ? AND provider_user_id = ?"
+        )
+        .bind(provider)
+        .bind(&profile.provider_user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match owner {
+            Some(owner) if owner == user_id => {}
+            Some(_) => {
+                return Err(AppError::Conflict(format!("This {} account is linked to another user", provider)));
+            }
+            None => {
+                let existing: i64 = sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM user_identity WHERE user_id = ? AND provider = ?"
+                )
+                .bind(user_id)
+                .bind(provider)
+                .fetch_one(pool)
+                .await?;
+
+                if existing > 0 {
+                    return Err(AppError::Conflict(format!(
+                        "Another {} account is already linked, unlink it first",
+                        provider
+                    )));
+                }
+
+                Self::insert(pool, user_id, provider, profile).await?;
+            }
+        }
+
+        Self::find_user(pool, provider, profile).await?;
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            "SELECT id, user_id, provider, provider_user_id, provider_login, email, created_at
+             FROM user_identity WHERE user_id = ? AND provider = ?"
+        )
+        .bind(user_id)
+        .bind(provider)
+        .fetch_one(pool)
+        .await?;
+        Ok(identity)
+    }
+
+    pub async fn insert<'e, E>(executor: E, user_id: i64, provider: &str, profile: &OAuthProfile) -> Result<(), AppError>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::MySql>,
+    {
+        sqlx::query(
+            "INSERT INTO user_identity (user_id, provider, provider_user_id, provider_login, email) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(&profile.provider_user_id)
+        .bind(&profile.login)
+        .bind(&profile.email)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_identities(pool: &MySqlPool, user_id: i64) -> Result<Vec<UserIdentity>, AppError> {
+        let identities = sqlx::query_as::<_, UserIdentity>(
+            "SELECT id, user_id, provider, provider_user_id, provider_login, email, created_at
+             FROM user_identity WHERE user_id = ? ORDER BY id"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(identities)
+    }
+
+    // 账号至少保留一种登录方式：有本地密码、可通过邮箱找回密码，或还有其他第三方账号 / 钱包
+    pub async fn can_remove_sign_in_method(pool: &MySqlPool, user_id: i64) -> Result<bool, AppError> {
+        let row = sqlx::query(
+            "SELECT u.password_hash <> '' OR u.email IS NOT NULL AS recoverable,
+                    (SELECT COUNT(*) FROM user_identity i WHERE i.user_id = u.id)
+                      + (SELECT COUNT(*) FROM user_wallet w WHERE w.user_id = u.id) AS methods
+             FROM sys_user u WHERE u.id = ?"
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+        Ok(row.get::<bool, _>("recoverable") || row.get::<i64, _>("methods") > 1)
+    }
+
+    pub async fn unlink(pool: &MySqlPool, user_id: i64, provider: &str) -> Result<(), AppError> {
+        let linked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_identity WHERE user_id = ? AND provider = ?")
+            .bind(user_id)
+            .bind(provider)
+            .fetch_one(pool)
+            .await?;
+
+        if linked == 0 {
+            return Err(AppError::NotFound(format!("No {} account is linked", provider)));
+        }
+        if !Self::can_remove_sign_in_method(pool, user_id).await? {
+            return Err(AppError::validation("Cannot unlink the only sign-in method of this account"));
+        }
+
+        sqlx::query("DELETE FROM user_identity WHERE user_id = ? AND provider = ?")
+            .bind(user_id)
+            .bind(provider)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
+
+// 需要 MySQL：L2E_TEST_DATABASE_URL=... cargo test -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oauth::github::GITHUB_PROVIDER;
+    use crate::test_support::{database_pool, insert_user};
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn login_state_is_single_use() {
+        let pool = database_pool().await;
+        let state = IdentityService::create_state(&pool, GITHUB_PROVIDER, None).await.unwrap();
+
+        let first = IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state).await.unwrap();
+        assert!(matches!(first, OAuthStateCheck::Login));
+        let second = IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state).await.unwrap();
+        assert!(matches!(second, OAuthStateCheck::Invalid));
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn link_state_is_single_use() {
+        let pool = database_pool().await;
+        let user_id = insert_user(&pool).await;
+        let state = IdentityService::create_state(&pool, GITHUB_PROVIDER, Some(user_id)).await.unwrap();
+
+        let first = IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state).await.unwrap();
+        assert!(matches!(first, OAuthStateCheck::Link { user_id: id } if id == user_id));
+        let second = IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state).await.unwrap();
+        assert!(matches!(second, OAuthStateCheck::Invalid));
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn state_is_bound_to_provider() {
+        let pool = database_pool().await;
+        let state = IdentityService::create_state(&pool, GITHUB_PROVIDER, None).await.unwrap();
+
+        let other = IdentityService::consume_state(&pool, "gitlab", &state).await.unwrap();
+        assert!(matches!(other, OAuthStateCheck::Invalid));
+        let github = IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state).await.unwrap();
+        assert!(matches!(github, OAuthStateCheck::Login));
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn concurrent_consumers_get_one_success() {
+        let pool = database_pool().await;
+        let state = IdentityService::create_state(&pool, GITHUB_PROVIDER, None).await.unwrap();
+
+        let (a, b) = tokio::join!(
+            IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state),
+            IdentityService::consume_state(&pool, GITHUB_PROVIDER, &state),
+        );
+        let logins = [a.unwrap(), b.unwrap()]
+            .iter()
+            .filter(|check| matches!(check, OAuthStateCheck::Login))
+            .count();
+        assert_eq!(logins, 1);
+    }
+}
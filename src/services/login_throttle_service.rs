@@ -0,0 +1,224 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::MySqlPool;
+use crate::config::settings::LoginThrottleSettings;
+use crate::error::AppError;
+
+// 失败计数的维度
+#[derive(Debug, Clone, Copy)]
+enum ThrottleScope {
+    Username,
+    Ip,
+}
+
+impl ThrottleScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleScope::Username => "username",
+            ThrottleScope::Ip => "ip",
+        }
+    }
+}
+
+pub fn too_many_requests(message: &str, until: DateTime<Utc>) -> AppError {
+    // 向上取整，至少 1 秒
+    let retry_after = (until - Utc::now()).num_milliseconds().max(0) as u64;
+    AppError::TooManyRequests {
+        message: message.to_string(),
+        retry_after: retry_after.div_ceil(1000).max(1),
+    }
+}
+
+// 连续失败超过阈值后的等待时间：base * 2^(failures - threshold)，不超过 max
+fn backoff_seconds(settings: &LoginThrottleSettings, failures: u32, threshold: u32) -> u64 {
+    if failures < threshold {
+        return 0;
+    }
+    let exponent = (failures - threshold).min(32);
+    settings
+        .backoff_base_seconds
+        .saturating_mul(1u64 << exponent)
+        .min(settings.backoff_max_seconds)
+}
+
+pub struct LoginThrottleService;
+
+impl LoginThrottleService {
+    // 登录前检查用户名 / IP 是否处于退避期
+    pub async fn check(pool: &MySqlPool, username: &str, ip: Option<&str>) -> Result<(), AppError> {
+        let mut subjects = vec![(ThrottleScope::Username, username)];
+        if let Some(ip) = ip {
+            subjects.push((ThrottleScope::Ip, ip));
+        }
+
+        for (scope, subject) in subjects {
+            let blocked_until: Option<Option<DateTime<Utc>>> = sqlx::query_scalar(
+                "SELECT blocked_until FROM sys_login_throttle WHERE scope = ? AND subject = ?"
+            )
+            .bind(scope.as_str())
+            .bind(subject)
+            .fetch_optional(pool)
+            .await?;
+
+            if let Some(Some(until)) = blocked_until
+                && until > Utc::now()
+            {
+                return Err(too_many_requests("Too many failed login attempts, please try again later", until));
+            }
+        }
+        Ok(())
+    }
+
+    // 账号处于锁定期时拒绝登录
+    pub fn check_user_lock(locked_until: Option<DateTime<Utc>>) -> Result<(), AppError> {
+        match locked_until {
+            Some(until) if until > Utc::now() => {
+                Err(too_many_requests("Account is temporarily locked due to failed login attempts", until))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // 记录一次失败；user_id 存在时累计账号失败次数，达到阈值后锁定账号
+    pub async fn record_failure(
+        pool: &MySqlPool,
+        settings: &LoginThrottleSettings,
+        username: &str,
+        ip: Option<&str>,
+        user_id: Option<i64>,
+    ) -> Result<(), AppError> {
+        Self::bump(pool, settings, ThrottleScope::Username, username, settings.backoff_after).await?;
+        if let Some(ip) = ip {
+            Self::bump(pool, settings, ThrottleScope::Ip, ip, settings.ip_backoff_after).await?;
+        }
+
+        if let Some(user_id) = user_id {
+            let locked_until = Utc::now() + Duration::minutes(settings.lockout_minutes);
+            sqlx::query(
+                "UPDATE sys_user SET
+                   locked_until = IF(failed_login_count + 1 >= ?, ?, locked_until),
+                   failed_login_count = IF(failed_login_count + 1 >= ?, 0, failed_login_count + 1)
+                 WHERE id = ?"
+            )
+            .bind(settings.lockout_threshold)
+            .bind(locked_until)
+            .bind(settings.lockout_threshold)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn bump(
+        pool: &MySqlPool,
+        settings: &LoginThrottleSettings,
+        scope: ThrottleScope,
+        subject: &str,
+        threshold: u32,
+    ) -> Result<(), AppError> {
+        // 超过统计窗口的旧失败记录重新计数
+        sqlx::query(
+            "INSERT INTO sys_login_throttle (scope, subject, failures, last_failed_at) VALUES (?, ?, 1, CURRENT_TIMESTAMP)
+             ON DUPLICATE KEY UPDATE
+               failures = IF(last_failed_at < DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? MINUTE), 1, failures + 1),
+               last_failed_at = CURRENT_TIMESTAMP"
+        )
+        .bind(scope.as_str())
+        .bind(subject)
+        .bind(settings.failure_window_minutes)
+        .execute(pool)
+        .await?;
+
+        let failures: i32 = sqlx::query_scalar("SELECT failures FROM sys_login_throttle WHERE scope = ? AND subject = ?")
+            .bind(scope.as_str())
+            .bind(subject)
+            .fetch_one(pool)
+            .await?;
+
+        let delay = backoff_seconds(settings, failures.max(0) as u32, threshold);
+        if delay > 0 {
+            sqlx::query("UPDATE sys_login_throttle SET blocked_until = ? WHERE scope = ? AND subject = ?")
+                .bind(Utc::now() + Duration::seconds(delay as i64))
+                .bind(scope.as_str())
+                .bind(subject)
+                .execute(pool)
+                .await?;
+        }
+
+        // 顺带清理长时间没有失败的记录
+        sqlx::query(
+            "DELETE FROM sys_login_throttle WHERE last_failed_at < DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? MINUTE)
+             AND (blocked_until IS NULL OR blocked_until < CURRENT_TIMESTAMP)"
+        )
+        .bind(settings.failure_window_minutes)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // 登录成功后清除该用户名和账号的失败计数；IP 计数保留，避免攻击者用自己的账号重置
+    pub async fn record_success(pool: &MySqlPool, username: &str, user_id: i64) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM sys_login_throttle WHERE scope = ? AND subject = ?")
+            .bind(ThrottleScope::Username.as_str())
+            .bind(username)
+            .execute(pool)
+            .await?;
+
+        sqlx::query("UPDATE sys_user SET failed_login_count = 0, locked_until = NULL WHERE id = ?")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // 管理员解锁账号
+    pub async fn unlock_user(pool: &MySqlPool, user_id: i64) -> Result<(), AppError> {
+        let username: Option<String> = sqlx::query_scalar("SELECT username FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(username) = username else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+
+        Self::record_success(pool, &username.to_lowercase(), user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> LoginThrottleSettings {
+        LoginThrottleSettings {
+            backoff_base_seconds: 2,
+            backoff_max_seconds: 60,
+            ..LoginThrottleSettings::default()
+        }
+    }
+
+    #[test]
+    fn no_backoff_below_threshold() {
+        let settings = settings();
+        for failures in 0..3 {
+            assert_eq!(backoff_seconds(&settings, failures, 3), 0);
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_after_threshold() {
+        let settings = settings();
+        let delays: Vec<u64> = (3..8).map(|failures| backoff_seconds(&settings, failures, 3)).collect();
+        assert_eq!(delays, vec![2, 4, 8, 16, 32]);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let settings = settings();
+        assert_eq!(backoff_seconds(&settings, 8, 3), 60);
+        assert_eq!(backoff_seconds(&settings, 100, 3), 60);
+        assert_eq!(backoff_seconds(&settings, u32::MAX, 3), 60);
+    }
+}
\ No newline at end of file
@@ -1,3 +1,13 @@
 pub mod task_service;
 pub mod task_submission_service;
-pub mod auth_service;
\ No newline at end of file
+pub mod auth_service;
+pub mod permission_service;
+pub mod token_service;
+pub mod payout_service;
+pub mod wallet_service;
+pub mod login_throttle_service;
+pub mod two_factor_service;
+pub mod identity_service;
+pub mod user_service;
+pub mod personal_token_service;
+pub mod session_service;
\ No newline at end of file
@@ -0,0 +1,715 @@
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySql, MySqlPool, Row};
+use std::sync::Arc;
+use crate::error::AppError;
+use crate::models::payout::{Payout, PayoutListResponse, PayoutQuery, PayoutStatus};
+use crate::models::task::PaginationInfo;
+use crate::models::task_submission::SubmissionStatus;
+use crate::payout::client::TxStatus;
+use crate::services::task_submission_service::TaskSubmissionService;
+use crate::services::wallet_service::WalletService;
+use crate::state::AppState;
+use crate::utils::sui::is_valid_sui_address;
+
+const PAYOUT_COLUMNS: &str = "id, submission_id, user_id, recipient_address, coin_type, amount, status, tx_digest, error, attempts, created_at, updated_at, confirmed_at";
+
+// error 列长度为 512
+const MAX_ERROR_LEN: usize = 512;
+
+fn payout_from_row(row: &MySqlRow) -> Result<Payout, AppError> {
+    let status: String = row.get("status");
+    let status = PayoutStatus::parse(&status)
+        .ok_or_else(|| AppError::Internal(format!("Unknown payout status '{}'", status)))?;
+
+    Ok(Payout {
+        id: row.get("id"),
+        submission_id: row.get("submission_id"),
+        user_id: row.get("user_id"),
+        recipient_address: row.get("recipient_address"),
+        coin_type: row.get("coin_type"),
+        amount: row.get("amount"),
+        status,
+        tx_digest: row.get("tx_digest"),
+        error: row.get("error"),
+        attempts: row.get("attempts"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        confirmed_at: row.get("confirmed_at"),
+    })
+}
+
+fn truncate_error(message: &str) -> String {
+    message.chars().take(MAX_ERROR_LEN).collect()
+}
+
+// 提交对应的链上奖励
+pub struct PayoutReward {
+    pub coin_type: String,
+    pub amount: u64,
+}
+
+pub struct PayoutService;
+
+impl PayoutService {
+    pub fn validate_recipient(recipient_address: Option<&str>) -> Result<(), AppError> {
+        if let Some(address) = recipient_address
+            && !is_valid_sui_address(address)
+        {
+            return Err(AppError::validation("recipient_address must be a 0x-prefixed 32-byte SUI address"));
+        }
+        Ok(())
+    }
+
+    // 根据任务的 reward_cny / reward_token 计算奖励，奖励为 0 时返回 None
+    pub async fn resolve_reward(state: &AppState, submission_id: i64) -> Result<Option<PayoutReward>, AppError> {
+        let settings = &state.settings.payout;
+        let row = sqlx::query(
+            "SELECT t.reward_cny, t.reward_token FROM task_submission s JOIN task t ON t.id = s.task_id WHERE s.id = ?"
+        )
+        .bind(submission_id)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("Submission not found".to_string()));
+        };
+
+        let reward_cny: i32 = row.get("reward_cny");
+        if reward_cny <= 0 {
+            return Ok(None);
+        }
+
+        let reward_token: String = row.get("reward_token");
+        let symbol = if reward_token.trim().is_empty() {
+            settings.default_token.as_str()
+        } else {
+            reward_token.trim()
+        };
+
+        let Some(token) = settings.token(symbol) else {
+            return Err(AppError::validation(format!("Reward token '{}' is not configured for payouts", symbol)));
+        };
+
+        let amount = (reward_cny as u64)
+            .checked_mul(token.amount_per_cny)
+            .ok_or_else(|| AppError::validation("Reward amount is too large"))?;
+
+        Ok(Some(PayoutReward {
+            coin_type: token.coin_type.clone(),
+            amount,
+        }))
+    }
+
+    // 为已通过的提交创建发放记录；同一提交重复调用时返回已有记录，
+    // 已有记录缺少收款地址时用本次提供的地址补全
+    pub async fn create_for_submission(
+        state: &AppState,
+        submission_id: i64,
+        recipient_address: Option<String>,
+    ) -> Result<Option<Payout>, AppError> {
+        let pool = &state.pool;
+
+        Self::validate_recipient(recipient_address.as_deref())?;
+
+        if let Some(existing) = Self::find_by_submission(pool, submission_id).await? {
+            if existing.status == PayoutStatus::AwaitingAddress
+                && let Some(address) = recipient_address
+            {
+                sqlx::query(
+                    "UPDATE payout SET recipient_address = ?, status = 'pending' WHERE id = ? AND status = 'awaiting_address'"
+                )
+                .bind(address)
+                .bind(existing.id)
+                .execute(pool)
+                .await?;
+                return Self::get_payout(pool, existing.id).await.map(Some);
+            }
+            return Ok(Some(existing));
+        }
+
+        let row = sqlx::query("SELECT user_id, status FROM task_submission WHERE id = ?")
+            .bind(submission_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("Submission not found".to_string()));
+        };
+
+        if row.get::<String, _>("status") != SubmissionStatus::Approved.as_str() {
+            return Err(AppError::Conflict("Only approved submissions can be paid out".to_string()));
+        }
+        let user_id: i64 = row.get("user_id");
+
+        // 未指定地址时使用学员绑定的默认钱包
+        let recipient_address = match recipient_address {
+            Some(address) => Some(address),
+            None => WalletService::primary_address(pool, user_id).await?,
+        };
+
+        let Some(reward) = Self::resolve_reward(state, submission_id).await? else {
+            return Ok(None);
+        };
+
+        Self::insert_payout(pool, submission_id, user_id, recipient_address, &reward).await?;
+        Self::find_by_submission(pool, submission_id).await
+    }
+
+    // 审核通过并创建发放记录，两者在同一事务中提交，不会出现已通过但没有发放记录的提交
+    pub async fn approve_with_payout(
+        state: &AppState,
+        submission_id: i64,
+        reviewer_id: i64,
+        comment: Option<String>,
+        recipient_address: Option<String>,
+    ) -> Result<Option<Payout>, AppError> {
+        let pool = &state.pool;
+
+        // 在修改状态前完成校验，避免审核成功但无法发放
+        Self::validate_recipient(recipient_address.as_deref())?;
+        let reward = Self::resolve_reward(state, submission_id).await?;
+
+        let mut tx = pool.begin().await?;
+        let user_id = TaskSubmissionService::approve_in(&mut tx, submission_id, reviewer_id, comment).await?;
+
+        let Some(reward) = reward else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        // 未指定地址时使用学员绑定的默认钱包
+        let recipient_address = match recipient_address {
+            Some(address) => Some(address),
+            None => WalletService::primary_address(pool, user_id).await?,
+        };
+
+        Self::insert_payout(&mut *tx, submission_id, user_id, recipient_address, &reward).await?;
+        tx.commit().await?;
+
+        Self::find_by_submission(pool, submission_id).await
+    }
+
+    // 有收款地址时为 pending，否则等待学员绑定钱包
+    async fn insert_payout<'e, E>(
+        executor: E,
+        submission_id: i64,
+        user_id: i64,
+        recipient_address: Option<String>,
+        reward: &PayoutReward,
+    ) -> Result<(), AppError>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
+        let status = if recipient_address.is_some() {
+            PayoutStatus::Pending
+        } else {
+            PayoutStatus::AwaitingAddress
+        };
+
+        // submission_id 唯一，并发审核时只会有一条记录写入成功
+        sqlx::query(
+            "INSERT IGNORE INTO payout (submission_id, user_id, recipient_address, coin_type, amount, status) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(submission_id)
+        .bind(user_id)
+        .bind(recipient_address)
+        .bind(&reward.coin_type)
+        .bind(reward.amount)
+        .bind(status.as_str())
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    // 学员绑定默认钱包后，为其等待地址的发放补全收款地址，返回可处理的发放 id
+    pub async fn assign_awaiting_address(
+        pool: &MySqlPool,
+        user_id: i64,
+        address: &str,
+    ) -> Result<Vec<i64>, AppError> {
+        let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM payout WHERE user_id = ? AND status = 'awaiting_address'")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        let mut assigned = Vec::new();
+        for id in ids {
+            let result = sqlx::query(
+                "UPDATE payout SET recipient_address = ?, status = 'pending' WHERE id = ? AND status = 'awaiting_address'"
+            )
+            .bind(address)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                assigned.push(id);
+            }
+        }
+        Ok(assigned)
+    }
+
+    // 执行一次发放：pending/failed -> processing -> submitted -> confirmed/failed
+    pub async fn process_payout(state: &AppState, payout_id: i64) -> Result<Payout, AppError> {
+        let pool = &state.pool;
+        let client = state.payout_client()?;
+
+        // 先抢占记录，保证同一笔发放不会被并发处理两次
+        let claimed = sqlx::query(
+            "UPDATE payout SET status = 'processing', attempts = attempts + 1, error = ''
+             WHERE id = ? AND status IN ('pending', 'failed') AND recipient_address IS NOT NULL"
+        )
+        .bind(payout_id)
+        .execute(pool)
+        .await?;
+
+        let payout = Self::get_payout(pool, payout_id).await?;
+        if claimed.rows_affected() == 0 {
+            return Err(AppError::Conflict(format!(
+                "Payout in status '{}' cannot be processed",
+                payout.status.as_str()
+            )));
+        }
+
+        let recipient = payout.recipient_address.clone().unwrap_or_default();
+        let prepared = match client.prepare_transfer(&recipient, &payout.coin_type, payout.amount).await {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                // 交易尚未提交，可以安全地标记为失败并重试
+                sqlx::query("UPDATE payout SET status = 'failed', error = ? WHERE id = ?")
+                    .bind(truncate_error(&e.to_string()))
+                    .bind(payout_id)
+                    .execute(pool)
+                    .await?;
+                return Self::get_payout(pool, payout_id).await;
+            }
+        };
+
+        // 提交前先记录 digest：即使提交结果未知，也可以通过同步接口查询链上状态
+        sqlx::query("UPDATE payout SET status = 'submitted', tx_digest = ? WHERE id = ?")
+            .bind(&prepared.digest)
+            .bind(payout_id)
+            .execute(pool)
+            .await?;
+
+        let local_digest = prepared.digest.clone();
+        match client.submit(prepared).await {
+            Ok(result) => {
+                // 以节点返回的 digest 为准
+                if result.digest != local_digest {
+                    sqlx::query("UPDATE payout SET tx_digest = ? WHERE id = ?")
+                        .bind(&result.digest)
+                        .bind(payout_id)
+                        .execute(pool)
+                        .await?;
+                }
+                Self::apply_tx_status(pool, payout_id, &result.status).await?
+            }
+            Err(e) => {
+                // 提交结果未知，保持 submitted，避免重试造成重复出款
+                sqlx::query("UPDATE payout SET error = ? WHERE id = ?")
+                    .bind(truncate_error(&e.to_string()))
+                    .bind(payout_id)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+
+        Self::get_payout(pool, payout_id).await
+    }
+
+    // 查询链上状态，更新已提交的发放
+    pub async fn sync_payout(state: &AppState, payout_id: i64) -> Result<Payout, AppError> {
+        let pool = &state.pool;
+        let client = state.payout_client()?;
+
+        let payout = Self::get_payout(pool, payout_id).await?;
+
+        let (PayoutStatus::Submitted, Some(digest)) = (payout.status, payout.tx_digest.as_deref()) else {
+            return Err(AppError::Conflict("Only submitted payouts can be synced".to_string()));
+        };
+
+        match client.transaction_status(digest).await {
+            Ok(status) => Self::apply_tx_status(pool, payout_id, &status).await?,
+            Err(e) => {
+                sqlx::query("UPDATE payout SET error = ? WHERE id = ?")
+                    .bind(truncate_error(&e.to_string()))
+                    .bind(payout_id)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+
+        Self::get_payout(pool, payout_id).await
+    }
+
+    async fn apply_tx_status(pool: &MySqlPool, payout_id: i64, status: &TxStatus) -> Result<(), AppError> {
+        match status {
+            TxStatus::Success => {
+                let result = sqlx::query(
+                    "UPDATE payout SET status = 'confirmed', error = '', confirmed_at = CURRENT_TIMESTAMP WHERE id = ? AND status = 'submitted'"
+                )
+                .bind(payout_id)
+                .execute(pool)
+                .await?;
+
+                // 到账后提交进入 paid 状态
+                if result.rows_affected() > 0 {
+                    let payout = Self::get_payout(pool, payout_id).await?;
+                    TaskSubmissionService::mark_paid(
+                        pool,
+                        payout.submission_id,
+                        payout.tx_digest.as_deref().unwrap_or_default(),
+                    )
+                    .await?;
+                }
+            }
+            TxStatus::Failure(message) => {
+                sqlx::query("UPDATE payout SET status = 'failed', error = ? WHERE id = ? AND status = 'submitted'")
+                    .bind(truncate_error(message))
+                    .bind(payout_id)
+                    .execute(pool)
+                    .await?;
+            }
+            TxStatus::Pending => {}
+        }
+        Ok(())
+    }
+
+    // processing 状态只覆盖构造和签名交易，交易 digest 在提交前已改为 submitted 状态写入；
+    // 超时仍处于 processing 说明处理进程中断且交易未提交，可以安全地标记为失败并重试。返回被恢复的发放 id
+    pub async fn recover_interrupted(pool: &MySqlPool, timeout_seconds: u64) -> Result<Vec<i64>, AppError> {
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM payout WHERE status = 'processing' AND updated_at < CURRENT_TIMESTAMP - INTERVAL ? SECOND"
+        )
+        .bind(timeout_seconds)
+        .fetch_all(pool)
+        .await?;
+
+        let mut recovered = Vec::new();
+        for id in ids {
+            let result = sqlx::query(
+                "UPDATE payout SET status = 'failed', error = 'Processing was interrupted'
+                 WHERE id = ? AND status = 'processing' AND updated_at < CURRENT_TIMESTAMP - INTERVAL ? SECOND"
+            )
+            .bind(id)
+            .bind(timeout_seconds)
+            .execute(pool)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                recovered.push(id);
+            }
+        }
+        Ok(recovered)
+    }
+
+    // 后台定时恢复中断的发放并重新处理
+    pub fn spawn_scheduler(state: Arc<AppState>) {
+        let settings = &state.settings.payout;
+        let interval_seconds = settings.recover_interval_seconds;
+        let timeout_seconds = settings.processing_timeout_seconds;
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(interval_seconds));
+            loop {
+                interval.tick().await;
+                match Self::recover_interrupted(&state.pool, timeout_seconds).await {
+                    Ok(ids) => {
+                        for id in ids {
+                            println!("Retrying interrupted payout {}", id);
+                            Self::spawn_process(state.clone(), id);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to recover interrupted payouts: {}", e),
+                }
+            }
+        });
+    }
+
+    // 在后台处理发放，不阻塞审核请求
+    pub fn spawn_process(state: Arc<AppState>, payout_id: i64) {
+        actix_web::rt::spawn(async move {
+            if let Err(e) = Self::process_payout(&state, payout_id).await {
+                eprintln!("Failed to process payout {}: {}", payout_id, e);
+            }
+        });
+    }
+
+    pub async fn get_payout(pool: &MySqlPool, payout_id: i64) -> Result<Payout, AppError> {
+        let sql = format!("SELECT {} FROM payout WHERE id = ?", PAYOUT_COLUMNS);
+        let row = sqlx::query(&sql)
+            .bind(payout_id)
+            .fetch_optional(pool)
+            .await?;
+
+        match row {
+            Some(row) => payout_from_row(&row),
+            None => Err(AppError::NotFound("Payout not found".to_string())),
+        }
+    }
+
+    pub async fn find_by_submission(pool: &MySqlPool, submission_id: i64) -> Result<Option<Payout>, AppError> {
+        let sql = format!("SELECT {} FROM payout WHERE submission_id = ?", PAYOUT_COLUMNS);
+        let row = sqlx::query(&sql)
+            .bind(submission_id)
+            .fetch_optional(pool)
+            .await?;
+
+        row.as_ref().map(payout_from_row).transpose()
+    }
+
+    pub async fn list_payouts(pool: &MySqlPool, query: PayoutQuery) -> Result<PayoutListResponse, AppError> {
+        let mut conditions = Vec::new();
+        if query.status.is_some() {
+            conditions.push("status = ?");
+        }
+        if query.user_id.is_some() {
+            conditions.push("user_id = ?");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        let count_sql = format!("SELECT COUNT(*) FROM payout{}", where_clause);
+        let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        if let Some(status) = query.status {
+            count_query = count_query.bind(status.as_str());
+        }
+        if let Some(user_id) = query.user_id {
+            count_query = count_query.bind(user_id);
+        }
+        let total = count_query.fetch_one(pool).await? as u32;
+
+        let data_sql = format!(
+            "SELECT {} FROM payout{} ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+            PAYOUT_COLUMNS, where_clause
+        );
+        let mut data_query = sqlx::query(&data_sql);
+        if let Some(status) = query.status {
+            data_query = data_query.bind(status.as_str());
+        }
+        if let Some(user_id) = query.user_id {
+            data_query = data_query.bind(user_id);
+        }
+        let rows = data_query
+            .bind(query.page_size())
+            .bind(query.offset())
+            .fetch_all(pool)
+            .await?;
+
+        let payouts = rows.iter().map(payout_from_row).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PayoutListResponse {
+            data: payouts,
+            pagination: PaginationInfo {
+                page: query.page(),
+                page_size: query.page_size(),
+                total,
+                total_pages: total.div_ceil(query.page_size()),
+            },
+        })
+    }
+}
+
+// 需要 MySQL：L2E_TEST_DATABASE_URL=... cargo test -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payout::mock::MockSuiRpc;
+    use crate::payout::signer::Ed25519Signer;
+    use crate::payout::PayoutClient;
+    use crate::test_support::{database_pool, insert_submission, insert_user, test_state};
+
+    const RECIPIENT: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    async fn payout_state() -> (Arc<MockSuiRpc>, AppState) {
+        let rpc = Arc::new(MockSuiRpc::new());
+        let mut state = test_state(database_pool().await);
+        state.payout_client = Some(PayoutClient::new(rpc.clone(), Arc::new(Ed25519Signer::generate()), 1_000));
+        (rpc, state)
+    }
+
+    // 已通过审核的提交和待处理的发放，返回 (submission_id, payout_id)
+    async fn pending_payout(pool: &MySqlPool) -> (i64, i64) {
+        let user_id = insert_user(pool).await;
+        let submission_id = insert_submission(pool, user_id, "approved").await;
+        let payout_id = sqlx::query(
+            "INSERT INTO payout (submission_id, user_id, recipient_address, coin_type, amount, status)
+             VALUES (?, ?, ?, '0x2::sui::SUI', 500, 'pending')"
+        )
+        .bind(submission_id)
+        .bind(user_id)
+        .bind(RECIPIENT)
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_id();
+        (submission_id, payout_id as i64)
+    }
+
+    async fn submission_status(pool: &MySqlPool, submission_id: i64) -> String {
+        sqlx::query_scalar("SELECT status FROM task_submission WHERE id = ?")
+            .bind(submission_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn process_confirms_payout_and_marks_submission_paid() {
+        let (rpc, state) = payout_state().await;
+        let (submission_id, payout_id) = pending_payout(&state.pool).await;
+
+        let payout = PayoutService::process_payout(&state, payout_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Confirmed);
+        assert_eq!(payout.attempts, 1);
+        assert!(payout.tx_digest.is_some());
+        assert!(payout.confirmed_at.is_some());
+        assert_eq!(rpc.executed(), 1);
+        assert_eq!(submission_status(&state.pool, submission_id).await, "paid");
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn pending_transaction_is_confirmed_by_sync() {
+        let (rpc, state) = payout_state().await;
+        let (submission_id, payout_id) = pending_payout(&state.pool).await;
+        rpc.queue_execution_status(TxStatus::Pending);
+
+        let payout = PayoutService::process_payout(&state, payout_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Submitted);
+        assert_eq!(submission_status(&state.pool, submission_id).await, "approved");
+
+        rpc.set_status(payout.tx_digest.as_deref().unwrap(), TxStatus::Success);
+        let payout = PayoutService::sync_payout(&state, payout_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Confirmed);
+        assert_eq!(submission_status(&state.pool, submission_id).await, "paid");
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn failed_payout_can_be_retried() {
+        let (rpc, state) = payout_state().await;
+        let (_, payout_id) = pending_payout(&state.pool).await;
+
+        // 构建交易失败：未提交，不记录 digest
+        rpc.fail_next_build("node unavailable");
+        let payout = PayoutService::process_payout(&state, payout_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Failed);
+        assert!(payout.error.contains("node unavailable"));
+        assert!(payout.tx_digest.is_none());
+
+        // 链上执行失败
+        rpc.queue_execution_status(TxStatus::Failure("InsufficientGas".to_string()));
+        let payout = PayoutService::process_payout(&state, payout_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Failed);
+        assert!(payout.error.contains("InsufficientGas"));
+
+        let payout = PayoutService::process_payout(&state, payout_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Confirmed);
+        assert_eq!(payout.attempts, 3);
+        assert!(payout.error.is_empty());
+        assert_eq!(rpc.executed(), 2);
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn payout_is_not_processed_twice() {
+        let (rpc, state) = payout_state().await;
+
+        let (_, payout_id) = pending_payout(&state.pool).await;
+        PayoutService::process_payout(&state, payout_id).await.unwrap();
+        let error = PayoutService::process_payout(&state, payout_id).await.unwrap_err();
+        assert!(matches!(error, AppError::Conflict(_)), "{:?}", error);
+        assert_eq!(rpc.executed(), 1);
+
+        // 并发处理同一笔发放时只有一个请求能抢占成功
+        let (_, payout_id) = pending_payout(&state.pool).await;
+        let (first, second) = tokio::join!(
+            PayoutService::process_payout(&state, payout_id),
+            PayoutService::process_payout(&state, payout_id),
+        );
+        assert_eq!([first.is_ok(), second.is_ok()].iter().filter(|ok| **ok).count(), 1);
+        assert_eq!(rpc.executed(), 2);
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn interrupted_processing_payout_is_recovered_and_retried() {
+        let (rpc, state) = payout_state().await;
+        let (submission_id, stale_id) = pending_payout(&state.pool).await;
+        let (_, fresh_id) = pending_payout(&state.pool).await;
+
+        // 模拟抢占后进程中断：stale 停留在 processing 超过超时时间，fresh 可能仍在处理中
+        sqlx::query(
+            "UPDATE payout SET status = 'processing', attempts = 1, updated_at = CURRENT_TIMESTAMP - INTERVAL 10 MINUTE WHERE id = ?"
+        )
+        .bind(stale_id)
+        .execute(&state.pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE payout SET status = 'processing', attempts = 1 WHERE id = ?")
+            .bind(fresh_id)
+            .execute(&state.pool)
+            .await
+            .unwrap();
+
+        let recovered = PayoutService::recover_interrupted(&state.pool, 300).await.unwrap();
+        assert!(recovered.contains(&stale_id));
+        assert!(!recovered.contains(&fresh_id));
+
+        let payout = PayoutService::get_payout(&state.pool, stale_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Failed);
+        assert_eq!(PayoutService::get_payout(&state.pool, fresh_id).await.unwrap().status, PayoutStatus::Processing);
+
+        let payout = PayoutService::process_payout(&state, stale_id).await.unwrap();
+        assert_eq!(payout.status, PayoutStatus::Confirmed);
+        assert_eq!(payout.attempts, 2);
+        assert_eq!(rpc.executed(), 1);
+        assert_eq!(submission_status(&state.pool, submission_id).await, "paid");
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn approval_creates_payout_with_the_submission() {
+        let (_, state) = payout_state().await;
+        let user_id = insert_user(&state.pool).await;
+        let reviewer_id = insert_user(&state.pool).await;
+        let submission_id = insert_submission(&state.pool, user_id, "in_review").await;
+
+        let payout = PayoutService::approve_with_payout(&state, submission_id, reviewer_id, None, Some(RECIPIENT.to_string()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(payout.submission_id, submission_id);
+        assert_eq!(payout.status, PayoutStatus::Pending);
+        assert_eq!(payout.recipient_address.as_deref(), Some(RECIPIENT));
+        assert_eq!(submission_status(&state.pool, submission_id).await, "approved");
+
+        // 未绑定钱包时等待学员补充地址
+        let submission_id = insert_submission(&state.pool, user_id, "in_review").await;
+        let payout = PayoutService::approve_with_payout(&state, submission_id, reviewer_id, None, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(payout.status, PayoutStatus::AwaitingAddress);
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn failed_approval_creates_no_payout() {
+        let (_, state) = payout_state().await;
+        let user_id = insert_user(&state.pool).await;
+        let reviewer_id = insert_user(&state.pool).await;
+        // withdrawn 不能转为 approved
+        let submission_id = insert_submission(&state.pool, user_id, "withdrawn").await;
+
+        let result = PayoutService::approve_with_payout(&state, submission_id, reviewer_id, None, Some(RECIPIENT.to_string())).await;
+        assert!(result.is_err());
+        assert!(PayoutService::find_by_submission(&state.pool, submission_id).await.unwrap().is_none());
+        assert_eq!(submission_status(&state.pool, submission_id).await, "withdrawn");
+    }
+}
@@ -0,0 +1,397 @@
+use sqlx::{MySql, MySqlPool, Transaction};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use crate::error::AppError;
+use crate::models::user::{
+    CreatePermissionRequest, CreateRoleRequest, RoleWithPermissions, SysPermission, SysRole, UpdatePermissionRequest,
+    UpdateRoleRequest,
+};
+
+// 权限集合的缓存时间，角色和授权变更时会主动失效
+const PERMISSION_CACHE_TTL: Duration = Duration::from_secs(60);
+
+// 权限集合及加载时间
+type CachedPermissions = (Arc<HashSet<String>>, Instant);
+
+// 进程内的用户权限缓存，避免每个请求都查询数据库
+#[derive(Default)]
+pub struct PermissionCache {
+    entries: RwLock<HashMap<i64, CachedPermissions>>,
+    // 每次失效递增，防止失效前开始的查询把旧结果写回缓存
+    generation: AtomicU64,
+}
+
+impl PermissionCache {
+    pub async fn get_or_load(&self, pool: &MySqlPool, user_id: i64) -> Result<Arc<HashSet<String>>, AppError> {
+        if let Some((permissions, loaded_at)) = self.entries.read().unwrap().get(&user_id)
+            && loaded_at.elapsed() < PERMISSION_CACHE_TTL
+        {
+            return Ok(Arc::clone(permissions));
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let permissions = PermissionService::get_user_permissions(pool, user_id).await?;
+        let permissions: Arc<HashSet<String>> = Arc::new(permissions.into_iter().collect());
+
+        let mut entries = self.entries.write().unwrap();
+        if self.generation.load(Ordering::Acquire) == generation {
+            entries.retain(|_, (_, loaded_at)| loaded_at.elapsed() < PERMISSION_CACHE_TTL);
+            entries.insert(user_id, (Arc::clone(&permissions), Instant::now()));
+        }
+        Ok(permissions)
+    }
+
+    pub fn invalidate_user(&self, user_id: i64) {
+        let mut entries = self.entries.write().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.remove(&user_id);
+    }
+
+    pub fn invalidate_all(&self) {
+        let mut entries = self.entries.write().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.clear();
+    }
+}
+
+// 角色编码：小写字母、数字、'_' 和 '-'
+fn role_key_problem(role_key: &str) -> Option<&'static str> {
+    if role_key.len() < 2 || role_key.len() > 64 {
+        return Some("must be 2-64 characters");
+    }
+    if !role_key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-') {
+        return Some("may only contain lowercase letters, digits, '_' and '-'");
+    }
+    None
+}
+
+// 权限编码形如 resource:action，各段规则同角色编码
+fn perm_key_problem(perm_key: &str) -> Option<&'static str> {
+    if perm_key.len() > 64 {
+        return Some("must be at most 64 characters");
+    }
+    let valid = perm_key.split(':').all(|part| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    });
+    if !valid {
+        return Some("must look like 'resource:action' using lowercase letters, digits, '_' and '-'");
+    }
+    None
+}
+
+fn name_problem(name: &str) -> Option<&'static str> {
+    if name.is_empty() || name.chars().count() > 64 {
+        return Some("must be 1-64 characters");
+    }
+    None
+}
+
+fn field_error(message: &str, field: &str, problem: &str) -> AppError {
+    AppError::validation_with_details(message, serde_json::json!({ field: problem }))
+}
+
+fn builtin_role() -> AppError {
+    AppError::Forbidden("Built-in roles cannot be modified".to_string())
+}
+
+fn builtin_permission() -> AppError {
+    AppError::Forbidden("Built-in permissions cannot be modified".to_string())
+}
+
+pub struct PermissionService;
+
+impl PermissionService {
+    // 获取用户启用中的角色编码
+    pub async fn get_user_roles(pool: &MySqlPool, user_id: i64) -> Result<Vec<String>, AppError> {
+        sqlx::query_scalar(
+            "SELECT r.role_key FROM sys_role r 
+             JOIN sys_user_role ur ON r.id = ur.role_id 
+             WHERE ur.user_id = ? AND r.status = 1"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // 通过 sys_user_role -> sys_role_perm 解析用户拥有的权限编码
+    pub async fn get_user_permissions(pool: &MySqlPool, user_id: i64) -> Result<Vec<String>, AppError> {
+        sqlx::query_scalar(
+            "SELECT DISTINCT p.perm_key FROM sys_permission p
+             JOIN sys_role_perm rp ON p.id = rp.perm_id
+             JOIN sys_role r ON rp.role_id = r.id
+             JOIN sys_user_role ur ON rp.role_id = ur.role_id
+             WHERE ur.user_id = ? AND r.status = 1"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // 获取角色，include_disabled 为 false 时只返回启用的角色
+    pub async fn list_roles(pool: &MySqlPool, include_disabled: bool) -> Result<Vec<SysRole>, AppError> {
+        let roles = sqlx::query_as::<_, SysRole>(
+            "SELECT id, role_key, role_name, status, is_builtin, created_at FROM sys_role
+             WHERE status = 1 OR ? ORDER BY id"
+        )
+        .bind(include_disabled)
+        .fetch_all(pool)
+        .await?;
+        Ok(roles)
+    }
+
+    // 获取所有权限
+    pub async fn list_permissions(pool: &MySqlPool) -> Result<Vec<SysPermission>, AppError> {
+        let permissions = sqlx::query_as::<_, SysPermission>(
+            "SELECT id, perm_key, perm_name, is_builtin FROM sys_permission ORDER BY id"
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(permissions)
+    }
+
+    pub async fn get_role(pool: &MySqlPool, role_id: i64) -> Result<RoleWithPermissions, AppError> {
+        let role = sqlx::query_as::<_, SysRole>(
+            "SELECT id, role_key, role_name, status, is_builtin, created_at FROM sys_role WHERE id = ?"
+        )
+        .bind(role_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Role not found".to_string()))?;
+
+        let permissions = sqlx::query_as::<_, SysPermission>(
+            "SELECT p.id, p.perm_key, p.perm_name, p.is_builtin FROM sys_permission p
+             JOIN sys_role_perm rp ON rp.perm_id = p.id
+             WHERE rp.role_id = ? ORDER BY p.id"
+        )
+        .bind(role_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(RoleWithPermissions { role, permissions })
+    }
+
+    pub async fn create_role(pool: &MySqlPool, request: CreateRoleRequest) -> Result<RoleWithPermissions, AppError> {
+        let role_key = request.role_key.trim();
+        let role_name = request.role_name.trim();
+        if let Some(problem) = role_key_problem(role_key) {
+            return Err(field_error("Invalid role", "role_key", problem));
+        }
+        if let Some(problem) = name_problem(role_name) {
+            return Err(field_error("Invalid role", "role_name", problem));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_role WHERE role_key = ?")
+            .bind(role_key)
+            .fetch_one(&mut *tx)
+            .await?;
+        if taken > 0 {
+            return Err(AppError::Conflict("The role key already exists".to_string()));
+        }
+
+        let result = sqlx::query("INSERT INTO sys_role (role_key, role_name) VALUES (?, ?)")
+            .bind(role_key)
+            .bind(role_name)
+            .execute(&mut *tx)
+            .await?;
+        let role_id = result.last_insert_id() as i64;
+
+        Self::assign_permissions(&mut tx, role_id, &request.permission_ids).await?;
+
+        tx.commit().await?;
+        Self::get_role(pool, role_id).await
+    }
+
+    // 修改角色名称或启用状态，角色编码创建后不可修改
+    pub async fn update_role(pool: &MySqlPool, role_id: i64, request: UpdateRoleRequest) -> Result<RoleWithPermissions, AppError> {
+        Self::ensure_custom_role(pool, role_id).await?;
+
+        if let Some(role_name) = &request.role_name {
+            let role_name = role_name.trim();
+            if let Some(problem) = name_problem(role_name) {
+                return Err(field_error("Invalid role", "role_name", problem));
+            }
+            sqlx::query("UPDATE sys_role SET role_name = ? WHERE id = ?")
+                .bind(role_name)
+                .bind(role_id)
+                .execute(pool)
+                .await?;
+        }
+
+        if let Some(status) = request.status {
+            if status != 0 && status != 1 {
+                return Err(field_error("Invalid role", "status", "must be 0 or 1"));
+            }
+            sqlx::query("UPDATE sys_role SET status = ? WHERE id = ?")
+                .bind(status)
+                .bind(role_id)
+                .execute(pool)
+                .await?;
+        }
+
+        Self::get_role(pool, role_id).await
+    }
+
+    // 删除角色；仍有用户持有的角色需先取消分配
+    pub async fn delete_role(pool: &MySqlPool, role_id: i64) -> Result<(), AppError> {
+        Self::ensure_custom_role(pool, role_id).await?;
+
+        let holders: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user_role WHERE role_id = ?")
+            .bind(role_id)
+            .fetch_one(pool)
+            .await?;
+        if holders > 0 {
+            return Err(AppError::Conflict(format!("The role is assigned to {} user(s)", holders)));
+        }
+
+        sqlx::query("DELETE FROM sys_role WHERE id = ? AND is_builtin = 0")
+            .bind(role_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // 整体替换角色拥有的权限
+    pub async fn set_role_permissions(pool: &MySqlPool, role_id: i64, permission_ids: &[i64]) -> Result<RoleWithPermissions, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let is_builtin: Option<bool> = sqlx::query_scalar("SELECT is_builtin FROM sys_role WHERE id = ? FOR UPDATE")
+            .bind(role_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        match is_builtin {
+            None => return Err(AppError::NotFound("Role not found".to_string())),
+            Some(true) => return Err(builtin_role()),
+            Some(false) => {}
+        }
+
+        Self::assign_permissions(&mut tx, role_id, permission_ids).await?;
+
+        tx.commit().await?;
+        Self::get_role(pool, role_id).await
+    }
+
+    pub async fn create_permission(pool: &MySqlPool, request: CreatePermissionRequest) -> Result<SysPermission, AppError> {
+        let perm_key = request.perm_key.trim();
+        let perm_name = request.perm_name.trim();
+        if let Some(problem) = perm_key_problem(perm_key) {
+            return Err(field_error("Invalid permission", "perm_key", problem));
+        }
+        if let Some(problem) = name_problem(perm_name) {
+            return Err(field_error("Invalid permission", "perm_name", problem));
+        }
+
+        let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_permission WHERE perm_key = ?")
+            .bind(perm_key)
+            .fetch_one(pool)
+            .await?;
+        if taken > 0 {
+            return Err(AppError::Conflict("The permission key already exists".to_string()));
+        }
+
+        let result = sqlx::query("INSERT INTO sys_permission (perm_key, perm_name) VALUES (?, ?)")
+            .bind(perm_key)
+            .bind(perm_name)
+            .execute(pool)
+            .await?;
+
+        Self::get_permission(pool, result.last_insert_id() as i64).await
+    }
+
+    // 只允许修改权限名称，权限编码被代码引用
+    pub async fn update_permission(pool: &MySqlPool, perm_id: i64, request: UpdatePermissionRequest) -> Result<SysPermission, AppError> {
+        let permission = Self::get_permission(pool, perm_id).await?;
+        if permission.is_builtin {
+            return Err(builtin_permission());
+        }
+
+        let perm_name = request.perm_name.trim();
+        if let Some(problem) = name_problem(perm_name) {
+            return Err(field_error("Invalid permission", "perm_name", problem));
+        }
+
+        sqlx::query("UPDATE sys_permission SET perm_name = ? WHERE id = ?")
+            .bind(perm_name)
+            .bind(perm_id)
+            .execute(pool)
+            .await?;
+
+        Self::get_permission(pool, perm_id).await
+    }
+
+    // 删除权限会同时从所有角色中移除
+    pub async fn delete_permission(pool: &MySqlPool, perm_id: i64) -> Result<(), AppError> {
+        let permission = Self::get_permission(pool, perm_id).await?;
+        if permission.is_builtin {
+            return Err(builtin_permission());
+        }
+
+        sqlx::query("DELETE FROM sys_permission WHERE id = ? AND is_builtin = 0")
+            .bind(perm_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_permission(pool: &MySqlPool, perm_id: i64) -> Result<SysPermission, AppError> {
+        sqlx::query_as::<_, SysPermission>("SELECT id, perm_key, perm_name, is_builtin FROM sys_permission WHERE id = ?")
+            .bind(perm_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Permission not found".to_string()))
+    }
+
+    async fn ensure_custom_role(pool: &MySqlPool, role_id: i64) -> Result<(), AppError> {
+        let is_builtin: Option<bool> = sqlx::query_scalar("SELECT is_builtin FROM sys_role WHERE id = ?")
+            .bind(role_id)
+            .fetch_optional(pool)
+            .await?;
+        match is_builtin {
+            None => Err(AppError::NotFound("Role not found".to_string())),
+            Some(true) => Err(builtin_role()),
+            Some(false) => Ok(()),
+        }
+    }
+
+    // 在事务中校验权限 ID 并写入角色权限
+    async fn assign_permissions(tx: &mut Transaction<'_, MySql>, role_id: i64, permission_ids: &[i64]) -> Result<(), AppError> {
+        let permission_ids: BTreeSet<i64> = permission_ids.iter().copied().collect();
+
+        if !permission_ids.is_empty() {
+            let placeholders = vec!["?"; permission_ids.len()].join(", ");
+            let sql = format!("SELECT id FROM sys_permission WHERE id IN ({}) FOR SHARE", placeholders);
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for perm_id in &permission_ids {
+                query = query.bind(perm_id);
+            }
+            let existing: BTreeSet<i64> = query.fetch_all(&mut **tx).await?.into_iter().collect();
+
+            let unknown: Vec<i64> = permission_ids.difference(&existing).copied().collect();
+            if !unknown.is_empty() {
+                return Err(AppError::validation_with_details(
+                    "Unknown permission ids",
+                    serde_json::json!({ "permission_ids": unknown }),
+                ));
+            }
+        }
+
+        sqlx::query("DELETE FROM sys_role_perm WHERE role_id = ?")
+            .bind(role_id)
+            .execute(&mut **tx)
+            .await?;
+
+        for perm_id in permission_ids {
+            sqlx::query("INSERT INTO sys_role_perm (role_id, perm_id) VALUES (?, ?)")
+                .bind(role_id)
+                .bind(perm_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+}
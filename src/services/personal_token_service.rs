@@ -0,0 +1,210 @@
+use chrono::{Duration, Utc};
+use sqlx::{MySqlPool, Row};
+use std::collections::{BTreeSet, HashMap};
+use crate::error::AppError;
+use crate::models::personal_token::{CreatePersonalTokenRequest, CreatePersonalTokenResponse, PersonalToken};
+use crate::models::user::USER_STATUS_ACTIVE;
+use crate::services::permission_service::PermissionService;
+use crate::utils::token::{generate_token, hash_token};
+
+// 个人访问令牌的固定前缀，认证时据此与 JWT 区分
+pub const PERSONAL_TOKEN_PREFIX: &str = "l2e_pat_";
+
+// 列表中展示的令牌前缀长度（含固定前缀）
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+const MAX_TOKENS_PER_USER: i64 = 20;
+const MAX_EXPIRES_IN_DAYS: u32 = 365;
+
+// 通过认证的个人访问令牌
+pub struct AuthenticatedToken {
+    pub token_id: i64,
+    pub user_id: i64,
+    pub scopes: Vec<String>,
+}
+
+pub struct PersonalTokenService;
+
+impl PersonalTokenService {
+    pub async fn list_tokens(pool: &MySqlPool, user_id: i64) -> Result<Vec<PersonalToken>, AppError> {
+        let rows = sqlx::query(
+            "SELECT id, name, token_prefix, expires_at, last_used_at, created_at
+             FROM personal_access_token WHERE user_id = ? ORDER BY id DESC"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        let scope_rows = sqlx::query(
+            "SELECT s.token_id, p.perm_key FROM personal_access_token_scope s
+             JOIN personal_access_token t ON t.id = s.token_id
+             JOIN sys_permission p ON p.id = s.perm_id
+             WHERE t.user_id = ? ORDER BY p.perm_key"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut scopes: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in scope_rows {
+            scopes.entry(row.get("token_id")).or_default().push(row.get("perm_key"));
+        }
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                PersonalToken {
+                    id,
+                    name: row.get("name"),
+                    token_prefix: row.get("token_prefix"),
+                    scopes: scopes.remove(&id).unwrap_or_default(),
+                    expires_at: row.get("expires_at"),
+                    last_used_at: row.get("last_used_at"),
+                    created_at: row.get("created_at"),
+                }
+            })
+            .collect())
+    }
+
+    // 创建令牌，令牌范围必须是用户当前拥有的权限
+    pub async fn create_token(
+        pool: &MySqlPool,
+        user_id: i64,
+        request: CreatePersonalTokenRequest,
+    ) -> Result<CreatePersonalTokenResponse, AppError> {
+        let mut field_errors = serde_json::Map::new();
+
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > 64 {
+            field_errors.insert("name".to_string(), "must be 1-64 characters".into());
+        }
+        if let Some(days) = request.expires_in_days
+            && !(1..=MAX_EXPIRES_IN_DAYS).contains(&days)
+        {
+            field_errors.insert(
+                "expires_in_days".to_string(),
+                format!("must be between 1 and {}", MAX_EXPIRES_IN_DAYS).into(),
+            );
+        }
+
+        let scopes: BTreeSet<&str> = request.scopes.iter().map(|scope| scope.trim()).collect();
+        let granted = PermissionService::get_user_permissions(pool, user_id).await?;
+        let not_granted: Vec<&str> = scopes
+            .iter()
+            .copied()
+            .filter(|scope| !granted.iter().any(|perm| perm == scope))
+            .collect();
+        if !not_granted.is_empty() {
+            field_errors.insert(
+                "scopes".to_string(),
+                serde_json::json!({ "not_granted": not_granted }),
+            );
+        }
+        if !field_errors.is_empty() {
+            return Err(AppError::validation_with_details("Invalid personal access token", field_errors.into()));
+        }
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM personal_access_token WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        if count >= MAX_TOKENS_PER_USER {
+            return Err(AppError::Conflict(format!(
+                "At most {} personal access tokens are allowed, revoke an unused one first",
+                MAX_TOKENS_PER_USER
+            )));
+        }
+
+        let token = format!("{}{}", PERSONAL_TOKEN_PREFIX, generate_token(40));
+        let token_prefix = &token[..DISPLAY_PREFIX_LEN];
+        let expires_at = request.expires_in_days.map(|days| Utc::now() + Duration::days(days as i64));
+
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query(
+            "INSERT INTO personal_access_token (user_id, name, token_prefix, token_hash, expires_at) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(token_prefix)
+        .bind(hash_token(&token))
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+        let token_id = result.last_insert_id() as i64;
+
+        for scope in &scopes {
+            sqlx::query("INSERT INTO personal_access_token_scope (token_id, perm_id) SELECT ?, id FROM sys_permission WHERE perm_key = ?")
+                .bind(token_id)
+                .bind(scope)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        let personal_token = Self::list_tokens(pool, user_id)
+            .await?
+            .into_iter()
+            .find(|t| t.id == token_id)
+            .ok_or_else(|| AppError::Internal("Created token not found".to_string()))?;
+
+        Ok(CreatePersonalTokenResponse { token, personal_token })
+    }
+
+    pub async fn revoke_token(pool: &MySqlPool, user_id: i64, token_id: i64) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM personal_access_token WHERE id = ? AND user_id = ?")
+            .bind(token_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Personal access token not found".to_string()));
+        }
+        Ok(())
+    }
+
+    // 校验令牌；过期、已吊销或所属用户不可用时返回 None
+    pub async fn authenticate(pool: &MySqlPool, token: &str) -> Result<Option<AuthenticatedToken>, AppError> {
+        let row = sqlx::query(
+            "SELECT t.id, t.user_id FROM personal_access_token t
+             JOIN sys_user u ON u.id = t.user_id
+             WHERE t.token_hash = ? AND (t.expires_at IS NULL OR t.expires_at > CURRENT_TIMESTAMP) AND u.status = ?"
+        )
+        .bind(hash_token(token))
+        .bind(USER_STATUS_ACTIVE)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let token_id: i64 = row.get("id");
+
+        let scopes = sqlx::query_scalar(
+            "SELECT p.perm_key FROM personal_access_token_scope s
+             JOIN sys_permission p ON p.id = s.perm_id
+             WHERE s.token_id = ?"
+        )
+        .bind(token_id)
+        .fetch_all(pool)
+        .await?;
+
+        // 最近使用时间精确到分钟即可，避免每个请求都写库
+        sqlx::query(
+            "UPDATE personal_access_token SET last_used_at = CURRENT_TIMESTAMP
+             WHERE id = ? AND (last_used_at IS NULL OR last_used_at < CURRENT_TIMESTAMP - INTERVAL 1 MINUTE)"
+        )
+        .bind(token_id)
+        .execute(pool)
+        .await?;
+
+        Ok(Some(AuthenticatedToken {
+            token_id,
+            user_id: row.get("user_id"),
+            scopes,
+        }))
+    }
+}
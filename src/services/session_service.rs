@@ -0,0 +1,112 @@
+use sqlx::{MySqlPool, Row};
+use crate::error::AppError;
+use crate::models::session::{ClientInfo, UserSession};
+use crate::services::token_service::TokenService;
+use crate::state::AppState;
+
+// user_agent 列的长度上限
+const MAX_USER_AGENT_LEN: usize = 512;
+
+pub struct SessionService;
+
+impl SessionService {
+    // 登录时创建会话，刷新令牌时更新客户端信息和最近活跃时间
+    pub async fn record(pool: &MySqlPool, user_id: i64, family_id: &str, client: &ClientInfo) -> Result<(), AppError> {
+        let user_agent = client
+            .user_agent
+            .as_deref()
+            .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect::<String>());
+
+        sqlx::query(
+            "INSERT INTO sys_session (user_id, family_id, ip_address, user_agent) VALUES (?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE
+               ip_address = COALESCE(VALUES(ip_address), ip_address),
+               user_agent = COALESCE(VALUES(user_agent), user_agent),
+               last_seen_at = CURRENT_TIMESTAMP"
+        )
+        .bind(user_id)
+        .bind(family_id)
+        .bind(&client.ip)
+        .bind(user_agent)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // 校验会话仍然有效，并按分钟粒度更新最近活跃时间
+    pub async fn touch(pool: &MySqlPool, family_id: &str) -> Result<bool, AppError> {
+        let row = sqlx::query(
+            "SELECT id, revoked_at IS NULL AS active, last_seen_at < CURRENT_TIMESTAMP - INTERVAL 1 MINUTE AS stale
+             FROM sys_session WHERE family_id = ?"
+        )
+        .bind(family_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(false);
+        };
+        if !row.get::<bool, _>("active") {
+            return Ok(false);
+        }
+
+        if row.get::<bool, _>("stale") {
+            sqlx::query("UPDATE sys_session SET last_seen_at = CURRENT_TIMESTAMP WHERE id = ?")
+                .bind(row.get::<i64, _>("id"))
+                .execute(pool)
+                .await?;
+        }
+        Ok(true)
+    }
+
+    // 仍可续期的会话，current_family_id 用于标记当前会话
+    pub async fn list_sessions(
+        pool: &MySqlPool,
+        user_id: i64,
+        current_family_id: &str,
+    ) -> Result<Vec<UserSession>, AppError> {
+        let sessions = sqlx::query_as::<_, UserSession>(
+            "SELECT s.id, s.ip_address, s.user_agent, s.created_at, s.last_seen_at, s.family_id = ? AS current
+             FROM sys_session s
+             WHERE s.user_id = ? AND s.revoked_at IS NULL
+               AND EXISTS(SELECT 1 FROM sys_refresh_token r
+                          WHERE r.family_id = s.family_id AND r.revoked_at IS NULL AND r.expires_at > CURRENT_TIMESTAMP)
+             ORDER BY s.last_seen_at DESC"
+        )
+        .bind(current_family_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(sessions)
+    }
+
+    // 远程登出某个会话，其 refresh token 和已签发的 access token 随即失效
+    pub async fn revoke_session(state: &AppState, user_id: i64, session_id: i64) -> Result<(), AppError> {
+        let family_id: Option<String> = sqlx::query_scalar(
+            "SELECT family_id FROM sys_session WHERE id = ? AND user_id = ? AND revoked_at IS NULL"
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        let Some(family_id) = family_id else {
+            return Err(AppError::NotFound("Session not found".to_string()));
+        };
+
+        TokenService::revoke_family(&state.pool, &state.settings.jwt, &family_id).await
+    }
+
+    // 管理员强制登出用户的所有会话
+    pub async fn revoke_all(state: &AppState, user_id: i64) -> Result<(), AppError> {
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(&state.pool)
+            .await?;
+        if exists == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        TokenService::revoke_user_sessions(&state.pool, &state.settings.jwt, user_id).await
+    }
+}
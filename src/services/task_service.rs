@@ -1,30 +1,148 @@
-use sqlx::{MySqlPool, Row};
-use crate::models::task::{Task, CreateTaskRequest, UpdateTaskRequest, TaskQuery, TaskListResponse, PaginationInfo};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySql, MySqlPool, QueryBuilder, Row};
+use std::sync::Arc;
+use crate::error::AppError;
+use crate::models::task::{
+    Task, TaskStatus, TaskDeadline, CreateTaskRequest, UpdateTaskRequest, TaskQuery, TaskListResponse, PaginationInfo,
+};
+use crate::state::AppState;
+
+const TASK_COLUMNS: &str =
+    "id, code, name, reward_cny, reward_token, description, status, opens_at, closes_at, created_at, updated_at";
+
+// 当前接受提交的任务：已发布且处于开放时间窗口内
+const OPEN_CONDITION: &str = "(status = 'published'
+    AND (opens_at IS NULL OR opens_at <= CURRENT_TIMESTAMP)
+    AND (closes_at IS NULL OR closes_at > CURRENT_TIMESTAMP))";
+
+fn task_from_row(row: &MySqlRow) -> Result<Task, AppError> {
+    let status: String = row.get("status");
+    let status = TaskStatus::parse(&status)
+        .ok_or_else(|| AppError::Internal(format!("Unknown task status '{}'", status)))?;
+
+    Ok(Task {
+        id: row.get("id"),
+        code: row.get("code"),
+        name: row.get("name"),
+        reward_cny: row.get("reward_cny"),
+        reward_token: row.get("reward_token"),
+        description: row.get("description"),
+        status,
+        opens_at: row.get("opens_at"),
+        closes_at: row.get("closes_at"),
+        due_at: None,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+// closing_within 的格式：数字加单位 m / h / d，如 48h
+fn parse_window(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit = value.chars().last()?;
+    let amount: i64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    if amount <= 0 {
+        return None;
+    }
+    match unit {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        _ => None,
+    }
+}
+
+// closes_at_changed 表示本次设置了 closes_at，新的关闭时间必须晚于当前时间
+fn validate_schedule(
+    opens_at: Option<DateTime<Utc>>,
+    closes_at: Option<DateTime<Utc>>,
+    closes_at_changed: bool,
+    now: DateTime<Utc>,
+    field_errors: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    if let (Some(opens_at), Some(closes_at)) = (opens_at, closes_at)
+        && closes_at <= opens_at
+    {
+        field_errors.insert("closes_at".to_string(), "must be after opens_at".into());
+    }
+    if closes_at_changed && closes_at.is_some_and(|closes_at| closes_at <= now) {
+        field_errors.insert("closes_at".to_string(), "must be in the future".into());
+    }
+}
+
+// 个人截止时间不能晚于任务的 closes_at
+fn validate_deadline(due_at: DateTime<Utc>, closes_at: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    if closes_at.is_some_and(|closes_at| due_at > closes_at) {
+        return Err(AppError::validation("due_at must not be after the task closes_at"));
+    }
+    Ok(())
+}
 
 pub struct TaskService;
 
 impl TaskService {
-    pub async fn get_tasks_with_pagination(pool: &MySqlPool, query: TaskQuery) -> Result<TaskListResponse, sqlx::Error> {
+    // include_unpublished 为 false 时（学员）只返回已发布的任务，忽略 status 筛选；已归档的任务需显式筛选
+    pub async fn get_tasks_with_pagination(
+        pool: &MySqlPool,
+        query: TaskQuery,
+        include_unpublished: bool,
+    ) -> Result<TaskListResponse, AppError> {
         let page = query.page();
         let page_size = query.page_size();
         let offset = query.offset();
         
+        let closing_within = match query.closing_within.as_deref() {
+            Some(value) => Some(
+                parse_window(value)
+                    .ok_or_else(|| AppError::validation("closing_within must look like 30m, 48h or 7d"))?,
+            ),
+            None => None,
+        };
+
         // 构建查询条件
-        let mut where_clause = String::new();
-        let mut count_where_clause = String::new();
+        let mut conditions = Vec::new();
         let mut params = Vec::new();
-        
-        if let Some(search) = &query.search {
-            if !search.trim().is_empty() {
-                where_clause = " WHERE name LIKE ? OR code LIKE ? OR description LIKE ?".to_string();
-                count_where_clause = where_clause.clone();
-                let search_param = format!("%{}%", search.trim());
-                params = vec![search_param.clone(), search_param.clone(), search_param];
+
+        let status = if include_unpublished { query.status } else { Some(TaskStatus::Published) };
+        match status {
+            Some(status) => {
+                conditions.push("status = ?".to_string());
+                params.push(status.as_str().to_string());
             }
+            None => conditions.push("status <> 'archived'".to_string()),
+        }
+
+        match query.open {
+            Some(true) => conditions.push(OPEN_CONDITION.to_string()),
+            Some(false) => conditions.push(format!("NOT {}", OPEN_CONDITION)),
+            None => {}
+        }
+
+        // 尚未关闭且将在指定时间内关闭
+        if let Some(window) = closing_within {
+            conditions.push(
+                "(closes_at > CURRENT_TIMESTAMP AND closes_at <= CURRENT_TIMESTAMP + INTERVAL ? SECOND)".to_string(),
+            );
+            params.push(window.num_seconds().to_string());
         }
         
+        if let Some(search) = &query.search
+            && !search.trim().is_empty()
+        {
+            conditions.push("(name LIKE ? OR code LIKE ? OR description LIKE ?)".to_string());
+            let search_param = format!("%{}%", search.trim());
+            params.extend([search_param.clone(), search_param.clone(), search_param]);
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        
         // 获取总数
-        let count_query = format!("SELECT COUNT(*) as total FROM task{}", count_where_clause);
+        let count_query = format!("SELECT COUNT(*) as total FROM task{}", where_clause);
         let mut count_sql = sqlx::query(&count_query);
         for param in &params {
             count_sql = count_sql.bind(param);
@@ -34,8 +152,8 @@ impl TaskService {
         
         // 获取分页数据
         let data_query = format!(
-            "SELECT id, code, name, reward_cny, reward_token, description, created_at, updated_at FROM task{} ORDER BY created_at DESC LIMIT ? OFFSET ?", 
-            where_clause
+            "SELECT {} FROM task{} ORDER BY created_at DESC LIMIT ? OFFSET ?", 
+            TASK_COLUMNS, where_clause
         );
         let mut data_sql = sqlx::query(&data_query);
         for param in &params {
@@ -45,18 +163,9 @@ impl TaskService {
         
         let rows = data_sql.fetch_all(pool).await?;
         
-        let tasks: Vec<Task> = rows.into_iter().map(|row| Task {
-            id: row.get("id"),
-            code: row.get("code"),
-            name: row.get("name"),
-            reward_cny: row.get("reward_cny"),
-            reward_token: row.get("reward_token"),
-            description: row.get("description"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        }).collect();
-
-        let total_pages = (total + page_size - 1) / page_size;
+        let tasks = rows.iter().map(task_from_row).collect::<Result<Vec<_>, _>>()?;
+
+        let total_pages = total.div_ceil(page_size);
 
         Ok(TaskListResponse {
             data: tasks,
@@ -69,104 +178,371 @@ impl TaskService {
         })
     }
 
-    pub async fn get_all_tasks(pool: &MySqlPool) -> Result<Vec<Task>, sqlx::Error> {
-        let rows = sqlx::query("SELECT id, code, name, reward_cny, reward_token, description, created_at, updated_at FROM task ORDER BY created_at DESC")
-            .fetch_all(pool)
-            .await?;
-
-        let tasks: Vec<Task> = rows.into_iter().map(|row| Task {
-            id: row.get("id"),
-            code: row.get("code"),
-            name: row.get("name"),
-            reward_cny: row.get("reward_cny"),
-            reward_token: row.get("reward_token"),
-            description: row.get("description"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        }).collect();
+    // 不含已归档的任务；include_unpublished 为 false 时只返回已发布的任务
+    pub async fn get_all_tasks(pool: &MySqlPool, include_unpublished: bool) -> Result<Vec<Task>, AppError> {
+        let sql = if include_unpublished {
+            format!("SELECT {} FROM task WHERE status <> 'archived' ORDER BY created_at DESC", TASK_COLUMNS)
+        } else {
+            format!("SELECT {} FROM task WHERE status = 'published' ORDER BY created_at DESC", TASK_COLUMNS)
+        };
+        let rows = sqlx::query(&sql).fetch_all(pool).await?;
 
-        Ok(tasks)
+        rows.iter().map(task_from_row).collect()
     }
 
-    pub async fn get_task_by_id(pool: &MySqlPool, id: i64) -> Result<Option<Task>, sqlx::Error> {
-        let row = sqlx::query("SELECT id, code, name, reward_cny, reward_token, description, created_at, updated_at FROM task WHERE id = ?")
+    // 草稿只对任务管理者可见；关闭和归档的任务仍可查看，已有提交会引用它们
+    pub async fn get_task_by_id(pool: &MySqlPool, id: i64, include_unpublished: bool) -> Result<Task, AppError> {
+        let sql = format!("SELECT {} FROM task WHERE id = ?", TASK_COLUMNS);
+        let row = sqlx::query(&sql)
             .bind(id)
             .fetch_optional(pool)
             .await?;
 
-        match row {
-            Some(row) => Ok(Some(Task {
-                id: row.get("id"),
-                code: row.get("code"),
-                name: row.get("name"),
-                reward_cny: row.get("reward_cny"),
-                reward_token: row.get("reward_token"),
-                description: row.get("description"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            })),
-            None => Ok(None),
+        let Some(row) = row else {
+            return Err(AppError::NotFound("Task not found".to_string()));
+        };
+        let task = task_from_row(&row)?;
+        if !include_unpublished && task.status == TaskStatus::Draft {
+            return Err(AppError::NotFound("Task not found".to_string()));
         }
+        Ok(task)
     }
 
-    pub async fn create_task(pool: &MySqlPool, task: CreateTaskRequest) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query("INSERT INTO task (code, name, reward_cny, reward_token, description) VALUES (?, ?, ?, ?, ?)")
+    pub async fn create_task(pool: &MySqlPool, task: CreateTaskRequest) -> Result<i64, AppError> {
+        // 校验字段，按字段返回错误明细
+        let mut field_errors = serde_json::Map::new();
+        if task.code.trim().is_empty() {
+            field_errors.insert("code".to_string(), "must not be empty".into());
+        }
+        if task.name.trim().is_empty() {
+            field_errors.insert("name".to_string(), "must not be empty".into());
+        }
+        if task.reward_cny < 0 {
+            field_errors.insert("reward_cny".to_string(), "must not be negative".into());
+        }
+        let status = task.status.unwrap_or(TaskStatus::Draft);
+        if !matches!(status, TaskStatus::Draft | TaskStatus::Published) {
+            field_errors.insert("status".to_string(), "must be draft or published".into());
+        }
+        validate_schedule(task.opens_at, task.closes_at, true, Utc::now(), &mut field_errors);
+        if !field_errors.is_empty() {
+            return Err(AppError::validation_with_details("Invalid task", field_errors.into()));
+        }
+
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM task WHERE code = ?")
             .bind(&task.code)
-            .bind(&task.name)
-            .bind(task.reward_cny)
-            .bind(&task.reward_token)
-            .bind(&task.description)
-            .execute(pool)
+            .fetch_one(pool)
             .await?;
+        if exists > 0 {
+            return Err(AppError::Conflict(format!("Task code '{}' already exists", task.code)));
+        }
+
+        let result = sqlx::query(
+            "INSERT INTO task (code, name, reward_cny, reward_token, description, status, opens_at, closes_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&task.code)
+        .bind(&task.name)
+        .bind(task.reward_cny)
+        .bind(&task.reward_token)
+        .bind(&task.description)
+        .bind(status.as_str())
+        .bind(task.opens_at)
+        .bind(task.closes_at)
+        .execute(pool)
+        .await?;
 
         Ok(result.last_insert_id() as i64)
     }
 
-    pub async fn update_task(pool: &MySqlPool, id: i64, task: UpdateTaskRequest) -> Result<bool, sqlx::Error> {
-        let mut query = String::from("UPDATE task SET ");
-        let mut params = Vec::new();
-        let mut updates = Vec::new();
+    pub async fn update_task(pool: &MySqlPool, id: i64, task: UpdateTaskRequest) -> Result<(), AppError> {
+        // 修改开放时间时与未修改的一侧合并后校验
+        if task.opens_at.is_some() || task.closes_at.is_some() {
+            let current = Self::get_task_by_id(pool, id, true).await?;
+            let mut field_errors = serde_json::Map::new();
+            validate_schedule(
+                task.opens_at.unwrap_or(current.opens_at),
+                task.closes_at.unwrap_or(current.closes_at),
+                task.closes_at.is_some(),
+                Utc::now(),
+                &mut field_errors,
+            );
+            if !field_errors.is_empty() {
+                return Err(AppError::validation_with_details("Invalid task", field_errors.into()));
+            }
+        }
+
+        let mut query = QueryBuilder::<MySql>::new("UPDATE task SET ");
+        let mut updates = query.separated(", ");
+        let mut updated = false;
 
-        if let Some(name) = &task.name {
-            updates.push("name = ?");
-            params.push(name.clone());
+        if let Some(name) = task.name {
+            updates.push("name = ").push_bind_unseparated(name);
+            updated = true;
         }
         if let Some(reward_cny) = task.reward_cny {
-            updates.push("reward_cny = ?");
-            params.push(reward_cny.to_string());
+            updates.push("reward_cny = ").push_bind_unseparated(reward_cny);
+            updated = true;
         }
-        if let Some(reward_token) = &task.reward_token {
-            updates.push("reward_token = ?");
-            params.push(reward_token.clone());
+        if let Some(reward_token) = task.reward_token {
+            updates.push("reward_token = ").push_bind_unseparated(reward_token);
+            updated = true;
         }
-        if let Some(description) = &task.description {
-            updates.push("description = ?");
-            params.push(description.clone());
+        if let Some(description) = task.description {
+            updates.push("description = ").push_bind_unseparated(description);
+            updated = true;
         }
+        if let Some(opens_at) = task.opens_at {
+            updates.push("opens_at = ").push_bind_unseparated(opens_at);
+            updated = true;
+        }
+        if let Some(closes_at) = task.closes_at {
+            updates.push("closes_at = ").push_bind_unseparated(closes_at);
+            updated = true;
+        }
+
+        if !updated {
+            return Err(AppError::validation("No fields to update"));
+        }
+
+        query.push(", updated_at = CURRENT_TIMESTAMP WHERE id = ").push_bind(id);
 
-        if updates.is_empty() {
-            return Ok(false);
+        let result = query.build().execute(pool).await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Task not found".to_string()));
         }
+        Ok(())
+    }
 
-        query.push_str(&updates.join(", "));
-        query.push_str(", updated_at = CURRENT_TIMESTAMP WHERE id = ?");
-        params.push(id.to_string());
+    // 按状态转换表修改任务状态，返回修改后的任务
+    pub async fn set_status(pool: &MySqlPool, id: i64, next: TaskStatus) -> Result<Task, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let row = sqlx::query("SELECT status, closes_at FROM task WHERE id = ? FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("Task not found".to_string()));
+        };
+        let status: String = row.get("status");
+        let current = TaskStatus::parse(&status)
+            .ok_or_else(|| AppError::Internal(format!("Unknown task status '{}'", status)))?;
+
+        if !current.can_transition_to(next) {
+            return Err(AppError::Conflict(format!(
+                "Cannot change task status from '{}' to '{}'",
+                current.as_str(),
+                next.as_str()
+            )));
+        }
 
-        let mut sql_query = sqlx::query(&query);
-        for param in params {
-            sql_query = sql_query.bind(param);
+        // 已过关闭时间的任务发布后会被立即关闭，需先修改 closes_at
+        let closes_at: Option<DateTime<Utc>> = row.get("closes_at");
+        if next == TaskStatus::Published && closes_at.is_some_and(|closes_at| closes_at <= Utc::now()) {
+            return Err(AppError::Conflict("Task closes_at has passed, update it before publishing".to_string()));
         }
 
-        let result = sql_query.execute(pool).await?;
-        Ok(result.rows_affected() > 0)
+        sqlx::query("UPDATE task SET status = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(next.as_str())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Self::get_task_by_id(pool, id, true).await
     }
 
-    pub async fn delete_task(pool: &MySqlPool, id: i64) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query("DELETE FROM task WHERE id = ?")
+    // 彻底删除已归档的任务及其全部提交；有已发放奖励的任务只能保持归档
+    pub async fn purge_task(pool: &MySqlPool, id: i64) -> Result<(), AppError> {
+        let task = Self::get_task_by_id(pool, id, true).await?;
+        if task.status != TaskStatus::Archived {
+            return Err(AppError::Conflict("Only archived tasks can be purged".to_string()));
+        }
+
+        // 发放记录引用提交，存在任何发放（包括处理中的）都不能删除
+        let paid: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM task_submission s
+             WHERE s.task_id = ? AND (s.status = 'paid' OR EXISTS(SELECT 1 FROM payout p WHERE p.submission_id = s.id))"
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+
+        if paid > 0 {
+            return Err(AppError::Conflict("Task has paid submissions and cannot be purged".to_string()));
+        }
+
+        // 提交及其历史通过外键级联删除
+        let result = sqlx::query("DELETE FROM task WHERE id = ? AND status = 'archived'")
             .bind(id)
             .execute(pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Only archived tasks can be purged".to_string()));
+        }
+        Ok(())
+    }
+
+    // 关闭已过 closes_at 的任务，返回关闭的数量
+    pub async fn close_expired(pool: &MySqlPool) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "UPDATE task SET status = 'closed', updated_at = CURRENT_TIMESTAMP
+             WHERE status = 'published' AND closes_at <= CURRENT_TIMESTAMP"
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    // 后台定时关闭到期的任务
+    pub fn spawn_scheduler(state: Arc<AppState>) {
+        let interval_seconds = state.settings.tasks.close_interval_seconds;
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(interval_seconds));
+            loop {
+                interval.tick().await;
+                match Self::close_expired(&state.pool).await {
+                    Ok(0) => {}
+                    Ok(closed) => println!("Closed {} expired tasks", closed),
+                    Err(e) => eprintln!("Failed to close expired tasks: {}", e),
+                }
+            }
+        });
+    }
+
+    pub async fn list_deadlines(pool: &MySqlPool, task_id: i64) -> Result<Vec<TaskDeadline>, AppError> {
+        Self::get_task_by_id(pool, task_id, true).await?;
+
+        let deadlines = sqlx::query_as::<_, TaskDeadline>(
+            "SELECT task_id, user_id, due_at, updated_at FROM task_deadline WHERE task_id = ? ORDER BY due_at"
+        )
+        .bind(task_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(deadlines)
+    }
+
+    pub async fn get_deadline(pool: &MySqlPool, task_id: i64, user_id: i64) -> Result<Option<DateTime<Utc>>, AppError> {
+        let due_at = sqlx::query_scalar("SELECT due_at FROM task_deadline WHERE task_id = ? AND user_id = ?")
+            .bind(task_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(due_at)
+    }
+
+    // 设置学员的个人截止时间，不能晚于任务的 closes_at
+    pub async fn set_deadline(
+        pool: &MySqlPool,
+        task_id: i64,
+        user_id: i64,
+        due_at: DateTime<Utc>,
+    ) -> Result<TaskDeadline, AppError> {
+        let task = Self::get_task_by_id(pool, task_id, true).await?;
+        validate_deadline(due_at, task.closes_at)?;
+
+        let user_exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        if user_exists == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        sqlx::query(
+            "INSERT INTO task_deadline (task_id, user_id, due_at) VALUES (?, ?, ?)
+             ON DUPLICATE KEY UPDATE due_at = VALUES(due_at)"
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .bind(due_at)
+        .execute(pool)
+        .await?;
+
+        let deadline = sqlx::query_as::<_, TaskDeadline>(
+            "SELECT task_id, user_id, due_at, updated_at FROM task_deadline WHERE task_id = ? AND user_id = ?"
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(deadline)
+    }
+
+    pub async fn delete_deadline(pool: &MySqlPool, task_id: i64, user_id: i64) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM task_deadline WHERE task_id = ? AND user_id = ?")
+            .bind(task_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Deadline not found".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 9, 1, hour, 0, 0).unwrap()
+    }
+
+    fn schedule_errors(
+        opens_at: Option<DateTime<Utc>>,
+        closes_at: Option<DateTime<Utc>>,
+        closes_at_changed: bool,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut field_errors = serde_json::Map::new();
+        validate_schedule(opens_at, closes_at, closes_at_changed, at(12), &mut field_errors);
+        field_errors
+    }
+
+    #[test]
+    fn parse_window_accepts_minutes_hours_and_days() {
+        assert_eq!(parse_window("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_window("48h"), Some(Duration::hours(48)));
+        assert_eq!(parse_window(" 7d "), Some(Duration::days(7)));
+    }
+
+    #[test]
+    fn parse_window_rejects_invalid_values() {
+        for value in ["", "h", "48", "0h", "-1h", "1.5h", "48x", "48H", "h48", "48小"] {
+            assert_eq!(parse_window(value), None, "{}", value);
+        }
+        assert_eq!(parse_window(&format!("{}d", i64::MAX)), None);
+    }
+
+    #[test]
+    fn schedule_requires_closes_at_after_opens_at() {
+        assert!(schedule_errors(Some(at(13)), Some(at(14)), true).is_empty());
+        assert!(schedule_errors(Some(at(14)), Some(at(14)), true).contains_key("closes_at"));
+        assert!(schedule_errors(Some(at(15)), Some(at(14)), true).contains_key("closes_at"));
+        assert!(schedule_errors(Some(at(15)), None, true).is_empty());
+        assert!(schedule_errors(None, Some(at(14)), true).is_empty());
+    }
+
+    #[test]
+    fn schedule_rejects_new_closes_at_in_the_past() {
+        assert!(schedule_errors(None, Some(at(12)), true).contains_key("closes_at"));
+        assert!(schedule_errors(None, Some(at(11)), true).contains_key("closes_at"));
+        // 未修改的 closes_at 不再校验，已关闭的任务仍可编辑其他字段
+        assert!(schedule_errors(None, Some(at(11)), false).is_empty());
+        assert!(schedule_errors(None, None, true).is_empty());
+    }
+
+    #[test]
+    fn deadline_must_not_be_after_closes_at() {
+        assert!(validate_deadline(at(13), Some(at(14))).is_ok());
+        assert!(validate_deadline(at(14), Some(at(14))).is_ok());
+        assert!(validate_deadline(at(15), Some(at(14))).is_err());
+        assert!(validate_deadline(at(15), None).is_ok());
     }
 }
\ No newline at end of file
@@ -1,84 +1,98 @@
-use sqlx::{MySqlPool, Row};
-use crate::models::task_submission::{TaskSubmission, TaskSubmissionQuery, TaskSubmissionListResponse};
-use crate::models::task::PaginationInfo;
-use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySql, MySqlPool, Row, Transaction};
+use crate::models::task_submission::{
+    TaskSubmission, TaskSubmissionQuery, TaskSubmissionListResponse, TaskSubmissionEvent,
+    CreateTaskSubmissionRequest, UpdateTaskSubmissionRequest, SubmissionStatus,
+};
+use crate::models::task::{PaginationInfo, TaskStatus};
+use crate::error::AppError;
+
+// comment 列长度为 512
+const MAX_COMMENT_LEN: usize = 512;
+
+fn parse_status(value: &str) -> Result<SubmissionStatus, AppError> {
+    SubmissionStatus::parse(value)
+        .ok_or_else(|| AppError::Internal(format!("Unknown submission status '{}'", value)))
+}
+
+fn submission_from_row(row: &MySqlRow) -> Result<TaskSubmission, AppError> {
+    Ok(TaskSubmission {
+        id: row.get("id"),
+        task_id: row.get("task_id"),
+        user_id: row.get("user_id"),
+        pr_url: row.get("pr_url"),
+        status: parse_status(&row.get::<String, _>("status"))?,
+        note: row.get("note"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+// 一次状态变更：actor_id 为空表示系统触发
+struct Transition {
+    to: SubmissionStatus,
+    actor_id: Option<i64>,
+    comment: Option<String>,
+}
 
 pub struct TaskSubmissionService;
 
 impl TaskSubmissionService {
+    // owner_id 不为空时只返回该用户自己的提交
     pub async fn get_submissions_by_task_id(
         pool: &MySqlPool,
         task_id: i64,
+        owner_id: Option<i64>,
         query: TaskSubmissionQuery,
-    ) -> Result<TaskSubmissionListResponse> {
+    ) -> Result<TaskSubmissionListResponse, AppError> {
         let mut where_clause = "WHERE task_id = ?".to_string();
-        let mut count_params = vec![task_id];
-        let mut data_params = vec![task_id];
-        
-        if let Some(ref status) = query.status {
+        let mut params = Vec::new();
+
+        if let Some(owner_id) = owner_id {
+            where_clause.push_str(" AND user_id = ?");
+            params.push(owner_id.to_string());
+        }
+        if let Some(status) = query.status {
             where_clause.push_str(" AND status = ?");
-            count_params.push(task_id); // 重新添加task_id用于count查询
-            data_params.push(task_id); // 重新添加task_id用于data查询
+            params.push(status.as_str().to_string());
         }
 
         // 查询总数
         let count_sql = format!("SELECT COUNT(*) FROM task_submission {}", where_clause);
-        let total_row = if let Some(ref status) = query.status {
-            sqlx::query(&count_sql)
-                .bind(task_id)
-                .bind(status)
-                .fetch_one(pool)
-                .await?
-        } else {
-            sqlx::query(&count_sql)
-                .bind(task_id)
-                .fetch_one(pool)
-                .await?
-        };
+        let mut count_query = sqlx::query(&count_sql).bind(task_id);
+        for param in &params {
+            count_query = count_query.bind(param);
+        }
+        let total_row = count_query.fetch_one(pool).await?;
         let total: u32 = total_row.get::<i64, _>(0) as u32;
 
         // 查询数据
         let data_sql = format!(
-            "SELECT id, task_id, user_id, pr_url, status, note, created_at, updated_at 
-             FROM task_submission {} 
-             ORDER BY created_at DESC 
+            "SELECT id, task_id, user_id, pr_url, status, note, created_at, updated_at
+             FROM task_submission {}
+             ORDER BY created_at DESC
              LIMIT ? OFFSET ?",
             where_clause
         );
 
-        let rows = if let Some(ref status) = query.status {
-            sqlx::query(&data_sql)
-                .bind(task_id)
-                .bind(status)
-                .bind(query.page_size())
-                .bind(query.offset())
-                .fetch_all(pool)
-                .await?
-        } else {
-            sqlx::query(&data_sql)
-                .bind(task_id)
-                .bind(query.page_size())
-                .bind(query.offset())
-                .fetch_all(pool)
-                .await?
-        };
+        let mut data_query = sqlx::query(&data_sql).bind(task_id);
+        for param in &params {
+            data_query = data_query.bind(param);
+        }
+        let rows = data_query
+            .bind(query.page_size())
+            .bind(query.offset())
+            .fetch_all(pool)
+            .await?;
 
-        let submissions: Vec<TaskSubmission> = rows
+        let submissions = rows
             .iter()
-            .map(|row| TaskSubmission {
-                id: row.get("id"),
-                task_id: row.get("task_id"),
-                user_id: row.get("user_id"),
-                pr_url: row.get("pr_url"),
-                status: row.get("status"),
-                note: row.get("note"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            })
-            .collect();
+            .map(submission_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total_pages = total.div_ceil(query.page_size());
 
-        let total_pages = (total + query.page_size() - 1) / query.page_size();
-        
         let response = TaskSubmissionListResponse {
             data: submissions,
             pagination: PaginationInfo {
@@ -92,70 +106,381 @@ impl TaskSubmissionService {
         Ok(response)
     }
 
+    // owner_id 不为空时只能查看该用户自己的提交
     pub async fn get_submission_by_id(
         pool: &MySqlPool,
         id: i64,
-    ) -> Result<Option<TaskSubmission>> {
+        owner_id: Option<i64>,
+    ) -> Result<TaskSubmission, AppError> {
         let row = sqlx::query(
-            "SELECT id, task_id, user_id, pr_url, status, note, created_at, updated_at 
+            "SELECT id, task_id, user_id, pr_url, status, note, created_at, updated_at
              FROM task_submission WHERE id = ?"
         )
         .bind(id)
         .fetch_optional(pool)
         .await?;
 
-        if let Some(row) = row {
-            Ok(Some(TaskSubmission {
-                id: row.get("id"),
-                task_id: row.get("task_id"),
-                user_id: row.get("user_id"),
-                pr_url: row.get("pr_url"),
-                status: row.get("status"),
-                note: row.get("note"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            }))
-        } else {
-            Ok(None)
+        let submission = match row {
+            Some(row) => submission_from_row(&row)?,
+            None => return Err(AppError::NotFound("Task submission not found".to_string())),
+        };
+        Self::ensure_viewable(submission.user_id, owner_id)?;
+        Ok(submission)
+    }
+
+    // 锁定提交并返回 (user_id, status)，用于在同一事务中校验和变更状态
+    async fn lock_submission(
+        tx: &mut Transaction<'_, MySql>,
+        submission_id: i64,
+    ) -> Result<(i64, SubmissionStatus), AppError> {
+        let row = sqlx::query("SELECT user_id, status FROM task_submission WHERE id = ? FOR UPDATE")
+            .bind(submission_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("Submission not found".to_string()));
+        };
+
+        Ok((row.get("user_id"), parse_status(&row.get::<String, _>("status"))?))
+    }
+
+    // 按转换表变更状态并记录历史，调用方需先通过 lock_submission 锁定记录
+    async fn apply_transition(
+        tx: &mut Transaction<'_, MySql>,
+        submission_id: i64,
+        from: SubmissionStatus,
+        transition: Transition,
+    ) -> Result<(), AppError> {
+        if !from.can_transition_to(transition.to) {
+            return Err(AppError::Conflict(format!(
+                "Cannot change submission from '{}' to '{}'",
+                from.as_str(),
+                transition.to.as_str()
+            )));
         }
+
+        sqlx::query("UPDATE task_submission SET status = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(transition.to.as_str())
+            .bind(submission_id)
+            .execute(&mut **tx)
+            .await?;
+
+        let comment: String = transition
+            .comment
+            .unwrap_or_default()
+            .trim()
+            .chars()
+            .take(MAX_COMMENT_LEN)
+            .collect();
+
+        sqlx::query(
+            "INSERT INTO task_submission_event (submission_id, actor_id, from_status, to_status, comment) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(submission_id)
+        .bind(transition.actor_id)
+        .bind(from.as_str())
+        .bind(transition.to.as_str())
+        .bind(comment)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // 在单独事务中执行一次状态变更
+    async fn change_status(
+        pool: &MySqlPool,
+        submission_id: i64,
+        transition: Transition,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let (_, from) = Self::lock_submission(&mut tx, submission_id).await?;
+        Self::apply_transition(&mut tx, submission_id, from, transition).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn start_review(
+        pool: &MySqlPool,
+        submission_id: i64,
+        reviewer_id: i64,
+    ) -> Result<(), AppError> {
+        Self::change_status(pool, submission_id, Transition {
+            to: SubmissionStatus::InReview,
+            actor_id: Some(reviewer_id),
+            comment: None,
+        })
+        .await
     }
 
     pub async fn approve_submission(
         pool: &MySqlPool,
         submission_id: i64,
-    ) -> Result<bool> {
+        reviewer_id: i64,
+        comment: Option<String>,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        Self::approve_in(&mut tx, submission_id, reviewer_id, comment).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 在调用方的事务中审核通过，返回提交者 ID；用于与发放记录一起提交
+    pub async fn approve_in(
+        tx: &mut Transaction<'_, MySql>,
+        submission_id: i64,
+        reviewer_id: i64,
+        comment: Option<String>,
+    ) -> Result<i64, AppError> {
+        let (owner_id, from) = Self::lock_submission(tx, submission_id).await?;
+        Self::apply_transition(tx, submission_id, from, Transition {
+            to: SubmissionStatus::Approved,
+            actor_id: Some(reviewer_id),
+            comment,
+        })
+        .await?;
+        Ok(owner_id)
+    }
+
+    // 审核意见记录在历史中，不再覆盖学员填写的 note
+    pub async fn reject_submission(
+        pool: &MySqlPool,
+        submission_id: i64,
+        reviewer_id: i64,
+        note: Option<String>,
+    ) -> Result<(), AppError> {
+        Self::change_status(pool, submission_id, Transition {
+            to: SubmissionStatus::Rejected,
+            actor_id: Some(reviewer_id),
+            comment: note,
+        })
+        .await
+    }
+
+    pub async fn request_changes(
+        pool: &MySqlPool,
+        submission_id: i64,
+        reviewer_id: i64,
+        note: String,
+    ) -> Result<(), AppError> {
+        if note.trim().is_empty() {
+            return Err(AppError::validation("note is required when requesting changes"));
+        }
+
+        Self::change_status(pool, submission_id, Transition {
+            to: SubmissionStatus::ChangesRequested,
+            actor_id: Some(reviewer_id),
+            comment: Some(note),
+        })
+        .await
+    }
+
+    // 奖励在链上确认到账后由系统标记为已发放
+    pub async fn mark_paid(pool: &MySqlPool, submission_id: i64, tx_digest: &str) -> Result<(), AppError> {
+        Self::change_status(pool, submission_id, Transition {
+            to: SubmissionStatus::Paid,
+            actor_id: None,
+            comment: Some(format!("Payout confirmed: {}", tx_digest)),
+        })
+        .await
+    }
+
+    // 学员撤回自己的提交
+    pub async fn withdraw_submission(
+        pool: &MySqlPool,
+        submission_id: i64,
+        user_id: i64,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let (owner_id, from) = Self::lock_submission(&mut tx, submission_id).await?;
+
+        if owner_id != user_id {
+            return Err(AppError::Forbidden("You can only withdraw your own submissions".to_string()));
+        }
+
+        Self::apply_transition(&mut tx, submission_id, from, Transition {
+            to: SubmissionStatus::Withdrawn,
+            actor_id: Some(user_id),
+            comment: None,
+        })
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 创建提交，user_id 来自 JWT，而不是请求体
+    pub async fn create_submission(
+        pool: &MySqlPool,
+        user_id: i64,
+        request: CreateTaskSubmissionRequest,
+    ) -> Result<i64, AppError> {
+        if request.pr_url.trim().is_empty() {
+            return Err(AppError::validation("pr_url is required"));
+        }
+
+        let task = sqlx::query(
+            "SELECT t.status, t.opens_at, t.closes_at, d.due_at
+             FROM task t LEFT JOIN task_deadline d ON d.task_id = t.id AND d.user_id = ?
+             WHERE t.id = ?"
+        )
+        .bind(user_id)
+        .bind(request.task_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(task) = task else {
+            return Err(AppError::NotFound("Task not found".to_string()));
+        };
+
+        // 草稿对学员不可见，按不存在处理
+        match TaskStatus::parse(task.get("status")) {
+            None | Some(TaskStatus::Draft) => return Err(AppError::NotFound("Task not found".to_string())),
+            Some(TaskStatus::Published) => {}
+            Some(_) => return Err(AppError::Conflict("Task is not accepting submissions".to_string())),
+        }
+
+        // 开放时间窗口和个人截止时间；关闭由后台任务定时执行，这里按当前时间再校验一次
+        let now = Utc::now();
+        let opens_at: Option<DateTime<Utc>> = task.get("opens_at");
+        let closes_at: Option<DateTime<Utc>> = task.get("closes_at");
+        let due_at: Option<DateTime<Utc>> = task.get("due_at");
+        if opens_at.is_some_and(|opens_at| opens_at > now) {
+            return Err(AppError::Conflict("Task is not open for submissions yet".to_string()));
+        }
+        if closes_at.is_some_and(|closes_at| closes_at <= now) {
+            return Err(AppError::Conflict("Task is closed for submissions".to_string()));
+        }
+        if due_at.is_some_and(|due_at| due_at <= now) {
+            return Err(AppError::Conflict("Your submission deadline has passed".to_string()));
+        }
+
+        let mut tx = pool.begin().await?;
+
         let result = sqlx::query(
-            "UPDATE task_submission SET status = 'approved', updated_at = CURRENT_TIMESTAMP WHERE id = ?"
+            "INSERT INTO task_submission (task_id, user_id, pr_url, status, note) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(request.task_id)
+        .bind(user_id)
+        .bind(request.pr_url.trim())
+        .bind(SubmissionStatus::Pending.as_str())
+        .bind(request.note.unwrap_or_default())
+        .execute(&mut *tx)
+        .await?;
+
+        let submission_id = result.last_insert_id() as i64;
+
+        sqlx::query(
+            "INSERT INTO task_submission_event (submission_id, actor_id, from_status, to_status) VALUES (?, ?, NULL, ?)"
         )
         .bind(submission_id)
-        .execute(pool)
+        .bind(user_id)
+        .bind(SubmissionStatus::Pending.as_str())
+        .execute(&mut *tx)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        tx.commit().await?;
+
+        Ok(submission_id)
     }
 
-    pub async fn reject_submission(
+    // 修改提交，仅提交者本人且状态为 pending / changes_requested 时允许；
+    // 退回修改的提交在修改后重新进入 pending
+    pub async fn update_submission(
         pool: &MySqlPool,
         submission_id: i64,
-        note: Option<String>,
-    ) -> Result<bool> {
-        let result = if let Some(note_text) = note {
-            sqlx::query(
-                "UPDATE task_submission SET status = 'rejected', note = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"
-            )
-            .bind(note_text)
-            .bind(submission_id)
-            .execute(pool)
-            .await?
-        } else {
-            sqlx::query(
-                "UPDATE task_submission SET status = 'rejected', updated_at = CURRENT_TIMESTAMP WHERE id = ?"
-            )
+        user_id: i64,
+        request: UpdateTaskSubmissionRequest,
+    ) -> Result<(), AppError> {
+        if request.pr_url.as_deref().is_some_and(|url| url.trim().is_empty()) {
+            return Err(AppError::validation("pr_url must not be empty"));
+        }
+
+        let mut tx = pool.begin().await?;
+        let (owner_id, status) = Self::lock_submission(&mut tx, submission_id).await?;
+
+        if owner_id != user_id {
+            return Err(AppError::Forbidden("You can only edit your own submissions".to_string()));
+        }
+        if !status.is_editable() {
+            return Err(AppError::Conflict(format!(
+                "Submissions in status '{}' cannot be edited",
+                status.as_str()
+            )));
+        }
+
+        sqlx::query(
+            "UPDATE task_submission SET pr_url = COALESCE(?, pr_url), note = COALESCE(?, note), updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?"
+        )
+        .bind(request.pr_url.map(|url| url.trim().to_string()))
+        .bind(request.note)
+        .bind(submission_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if status == SubmissionStatus::ChangesRequested {
+            Self::apply_transition(&mut tx, submission_id, status, Transition {
+                to: SubmissionStatus::Pending,
+                actor_id: Some(user_id),
+                comment: Some("Resubmitted".to_string()),
+            })
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    fn ensure_viewable(submitter_id: i64, owner_id: Option<i64>) -> Result<(), AppError> {
+        if owner_id.is_some_and(|owner_id| owner_id != submitter_id) {
+            return Err(AppError::Forbidden("You can only view your own submissions".to_string()));
+        }
+        Ok(())
+    }
+
+    // 审核意见和操作记录同样只对提交者本人和审核人员可见
+    pub async fn get_submission_history(
+        pool: &MySqlPool,
+        submission_id: i64,
+        owner_id: Option<i64>,
+    ) -> Result<Vec<TaskSubmissionEvent>, AppError> {
+        let submitter_id: Option<i64> = sqlx::query_scalar("SELECT user_id FROM task_submission WHERE id = ?")
             .bind(submission_id)
-            .execute(pool)
-            .await?
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(submitter_id) = submitter_id else {
+            return Err(AppError::NotFound("Submission not found".to_string()));
         };
+        Self::ensure_viewable(submitter_id, owner_id)?;
 
-        Ok(result.rows_affected() > 0)
+        let rows = sqlx::query(
+            "SELECT e.id, e.submission_id, e.actor_id, u.username AS actor_username, e.from_status, e.to_status, e.comment, e.created_at
+             FROM task_submission_event e
+             LEFT JOIN sys_user u ON u.id = e.actor_id
+             WHERE e.submission_id = ?
+             ORDER BY e.created_at, e.id"
+        )
+        .bind(submission_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let from_status: Option<String> = row.get("from_status");
+                Ok(TaskSubmissionEvent {
+                    id: row.get("id"),
+                    submission_id: row.get("submission_id"),
+                    actor_id: row.get("actor_id"),
+                    actor_username: row.get("actor_username"),
+                    from_status: from_status.as_deref().map(parse_status).transpose()?,
+                    to_status: parse_status(&row.get::<String, _>("to_status"))?,
+                    comment: row.get("comment"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,293 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{MySqlPool, Row};
+use crate::config::settings::JwtSettings;
+use crate::error::AppError;
+use crate::utils::token::{generate_token, hash_token};
+
+// 消费 refresh token 的结果
+pub enum RefreshTokenCheck {
+    Valid { user_id: i64, family_id: String },
+    Invalid,
+    // 已轮换过的 token 被再次使用，整个 family 已被吊销
+    Reused,
+}
+
+// 一次性用户令牌的用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserTokenPurpose {
+    EmailVerification,
+    PasswordReset,
+    TwoFactorLogin,
+}
+
+impl UserTokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserTokenPurpose::EmailVerification => "email_verification",
+            UserTokenPurpose::PasswordReset => "password_reset",
+            UserTokenPurpose::TwoFactorLogin => "two_factor_login",
+        }
+    }
+}
+
+pub struct TokenService;
+
+impl TokenService {
+    // 签发 refresh token 并落库（仅保存摘要），返回明文
+    pub async fn issue_refresh_token(
+        pool: &MySqlPool,
+        jwt: &JwtSettings,
+        user_id: i64,
+        family_id: &str,
+        access_jti: &str,
+    ) -> Result<String, AppError> {
+        let token = generate_token(64);
+        let expires_at = Utc::now() + Duration::days(jwt.refresh_ttl_days);
+
+        sqlx::query(
+            "INSERT INTO sys_refresh_token (user_id, family_id, token_hash, access_jti, expires_at) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(family_id)
+        .bind(hash_token(&token))
+        .bind(access_jti)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    // 轮换时消费旧 token：每个 refresh token 只能使用一次
+    pub async fn consume_refresh_token(
+        pool: &MySqlPool,
+        jwt: &JwtSettings,
+        token: &str,
+    ) -> Result<RefreshTokenCheck, AppError> {
+        let row = sqlx::query(
+            "SELECT id, user_id, family_id, expires_at, revoked_at FROM sys_refresh_token WHERE token_hash = ?"
+        )
+        .bind(hash_token(token))
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(RefreshTokenCheck::Invalid);
+        };
+
+        let id: i64 = row.get("id");
+        let user_id: i64 = row.get("user_id");
+        let family_id: String = row.get("family_id");
+        let expires_at: DateTime<Utc> = row.get("expires_at");
+        let revoked_at: Option<DateTime<Utc>> = row.get("revoked_at");
+
+        if revoked_at.is_some() {
+            Self::revoke_family(pool, jwt, &family_id).await?;
+            return Ok(RefreshTokenCheck::Reused);
+        }
+        if expires_at <= Utc::now() {
+            return Ok(RefreshTokenCheck::Invalid);
+        }
+
+        // 并发请求同一个 token 时只有一个能成功，其余视为重放
+        let result = sqlx::query(
+            "UPDATE sys_refresh_token SET revoked_at = CURRENT_TIMESTAMP WHERE id = ? AND revoked_at IS NULL"
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            Self::revoke_family(pool, jwt, &family_id).await?;
+            return Ok(RefreshTokenCheck::Reused);
+        }
+
+        Ok(RefreshTokenCheck::Valid { user_id, family_id })
+    }
+
+    // 根据 refresh token 吊销其所属 family（登出），token 不存在时返回 false
+    pub async fn revoke_by_refresh_token(
+        pool: &MySqlPool,
+        jwt: &JwtSettings,
+        token: &str,
+    ) -> Result<bool, AppError> {
+        let family_id: Option<String> = sqlx::query_scalar(
+            "SELECT family_id FROM sys_refresh_token WHERE token_hash = ?"
+        )
+        .bind(hash_token(token))
+        .fetch_optional(pool)
+        .await?;
+
+        match family_id {
+            Some(family_id) => {
+                Self::revoke_family(pool, jwt, &family_id).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // 吊销整个 family，并把仍可能有效的 access token 加入吊销列表
+    pub async fn revoke_family(pool: &MySqlPool, jwt: &JwtSettings, family_id: &str) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "INSERT IGNORE INTO sys_revoked_token (jti, expires_at)
+             SELECT access_jti, DATE_ADD(created_at, INTERVAL ? MINUTE) FROM sys_refresh_token
+             WHERE family_id = ? AND created_at > DATE_SUB(CURRENT_TIMESTAMP, INTERVAL ? MINUTE)"
+        )
+        .bind(jwt.access_ttl_minutes)
+        .bind(family_id)
+        .bind(jwt.access_ttl_minutes)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE sys_refresh_token SET revoked_at = CURRENT_TIMESTAMP WHERE family_id = ? AND revoked_at IS NULL"
+        )
+        .bind(family_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE sys_session SET revoked_at = CURRENT_TIMESTAMP WHERE family_id = ? AND revoked_at IS NULL")
+            .bind(family_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // 顺带清理已过期的吊销记录
+        sqlx::query("DELETE FROM sys_revoked_token WHERE expires_at < CURRENT_TIMESTAMP")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 吊销用户的所有会话（修改 / 重置密码、禁用账号或管理员强制登出）
+    pub async fn revoke_user_sessions(pool: &MySqlPool, jwt: &JwtSettings, user_id: i64) -> Result<(), AppError> {
+        let family_ids: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT family_id FROM sys_refresh_token WHERE user_id = ? AND revoked_at IS NULL"
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        for family_id in family_ids {
+            Self::revoke_family(pool, jwt, &family_id).await?;
+        }
+
+        // refresh token 已全部过期的会话一并标记
+        sqlx::query("UPDATE sys_session SET revoked_at = CURRENT_TIMESTAMP WHERE user_id = ? AND revoked_at IS NULL")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // 作废用户某一用途下所有未使用的一次性令牌
+    pub async fn invalidate_user_tokens(
+        pool: &MySqlPool,
+        user_id: i64,
+        purpose: UserTokenPurpose,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE sys_user_token SET used_at = CURRENT_TIMESTAMP WHERE user_id = ? AND purpose = ? AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn is_access_token_revoked(pool: &MySqlPool, jti: &str) -> Result<bool, AppError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_revoked_token WHERE jti = ?")
+            .bind(jti)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    // 签发一次性用户令牌，同一用途下旧的未使用令牌随即作废
+    pub async fn issue_user_token(
+        pool: &MySqlPool,
+        user_id: i64,
+        purpose: UserTokenPurpose,
+        ttl: Duration,
+    ) -> Result<String, AppError> {
+        let token = generate_token(48);
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE sys_user_token SET used_at = CURRENT_TIMESTAMP WHERE user_id = ? AND purpose = ? AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO sys_user_token (user_id, purpose, token_hash, expires_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(hash_token(&token))
+        .bind(Utc::now() + ttl)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(token)
+    }
+
+    // 查询未使用的一次性用户令牌但不消费，返回 user_id
+    pub async fn find_user_token(
+        pool: &MySqlPool,
+        purpose: UserTokenPurpose,
+        token: &str,
+    ) -> Result<Option<i64>, AppError> {
+        let user_id = sqlx::query_scalar(
+            "SELECT user_id FROM sys_user_token
+             WHERE token_hash = ? AND purpose = ? AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP"
+        )
+        .bind(hash_token(token))
+        .bind(purpose.as_str())
+        .fetch_optional(pool)
+        .await?;
+        Ok(user_id)
+    }
+
+    // 消费一次性用户令牌，成功时返回 user_id；令牌无效、过期或已使用时返回 None
+    pub async fn consume_user_token(
+        pool: &MySqlPool,
+        purpose: UserTokenPurpose,
+        token: &str,
+    ) -> Result<Option<i64>, AppError> {
+        let token_hash = hash_token(token);
+
+        let row = sqlx::query(
+            "SELECT id, user_id FROM sys_user_token
+             WHERE token_hash = ? AND purpose = ? AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP"
+        )
+        .bind(&token_hash)
+        .bind(purpose.as_str())
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        // 并发使用同一令牌时只有一个请求成功
+        let result = sqlx::query("UPDATE sys_user_token SET used_at = CURRENT_TIMESTAMP WHERE id = ? AND used_at IS NULL")
+            .bind(row.get::<i64, _>("id"))
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        Ok(Some(row.get("user_id")))
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::Utc;
+use rand::{thread_rng, RngCore};
+use sqlx::{MySqlPool, Row};
+use crate::error::AppError;
+use crate::models::two_factor::{TwoFactorSetupResponse, TwoFactorStatusResponse};
+use crate::state::AppState;
+use crate::utils::token::hash_token;
+use crate::utils::totp;
+
+// 启用两步验证时生成的恢复码数量
+const RECOVERY_CODE_COUNT: usize = 10;
+
+// 恢复码格式 xxxxx-xxxxx（小写十六进制）
+fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 5];
+    thread_rng().fill_bytes(&mut bytes);
+    let code = hex::encode(bytes);
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+// 输入时允许省略分隔符、大小写不敏感
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn invalid_code() -> AppError {
+    AppError::validation_with_details(
+        "Invalid verification code",
+        serde_json::json!({ "code": "is invalid or has already been used" }),
+    )
+}
+
+pub struct TwoFactorService;
+
+impl TwoFactorService {
+    pub async fn is_enabled(pool: &MySqlPool, user_id: i64) -> Result<bool, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sys_user_totp WHERE user_id = ? AND enabled_at IS NOT NULL"
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn status(pool: &MySqlPool, user_id: i64) -> Result<TwoFactorStatusResponse, AppError> {
+        let enabled = Self::is_enabled(pool, user_id).await?;
+        let remaining: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sys_user_recovery_code WHERE user_id = ? AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(TwoFactorStatusResponse {
+            enabled,
+            recovery_codes_remaining: if enabled { remaining } else { 0 },
+        })
+    }
+
+    // 生成新的 TOTP 密钥，需调用 enable 提交验证码确认后才生效
+    pub async fn setup(state: &AppState, user_id: i64) -> Result<TwoFactorSetupResponse, AppError> {
+        let pool = &state.pool;
+
+        if Self::is_enabled(pool, user_id).await? {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+        }
+
+        let username: Option<String> = sqlx::query_scalar("SELECT username FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(username) = username else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+
+        let secret = totp::generate_secret();
+        // 已启用的密钥不会被覆盖
+        sqlx::query(
+            "INSERT INTO sys_user_totp (user_id, secret) VALUES (?, ?)
+             ON DUPLICATE KEY UPDATE
+               secret = IF(enabled_at IS NULL, VALUES(secret), secret),
+               last_used_step = IF(enabled_at IS NULL, NULL, last_used_step)"
+        )
+        .bind(user_id)
+        .bind(hex::encode(&secret))
+        .execute(pool)
+        .await?;
+
+        Ok(TwoFactorSetupResponse {
+            secret: totp::base32_encode(&secret),
+            otpauth_uri: totp::otpauth_uri(&state.settings.auth.two_factor.issuer, &username, &secret),
+        })
+    }
+
+    // 用验证码确认密钥后启用两步验证，返回一次性展示的恢复码
+    pub async fn enable(pool: &MySqlPool, user_id: i64, code: &str) -> Result<Vec<String>, AppError> {
+        let row = sqlx::query("SELECT secret, enabled_at IS NOT NULL AS enabled FROM sys_user_totp WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::validation("Two-factor setup has not been started"));
+        };
+        if row.get::<bool, _>("enabled") {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+        }
+
+        let secret = hex::decode(row.get::<String, _>("secret"))
+            .map_err(|e| AppError::Internal(format!("Corrupted TOTP secret: {}", e)))?;
+        let Some(step) = totp::verify(&secret, code.trim(), Utc::now().timestamp(), None) else {
+            return Err(invalid_code());
+        };
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query(
+            "UPDATE sys_user_totp SET enabled_at = CURRENT_TIMESTAMP, last_used_step = ? WHERE user_id = ? AND enabled_at IS NULL"
+        )
+        .bind(step)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+        }
+
+        sqlx::query("DELETE FROM sys_user_recovery_code WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for code in &codes {
+            sqlx::query("INSERT INTO sys_user_recovery_code (user_id, code_hash) VALUES (?, ?)")
+                .bind(user_id)
+                .bind(hash_token(&normalize_recovery_code(code)))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(codes)
+    }
+
+    // 校验 TOTP 验证码或恢复码；同一时间步的验证码和已使用的恢复码不能再次使用
+    pub async fn verify_code(pool: &MySqlPool, user_id: i64, code: &str) -> Result<bool, AppError> {
+        let code = code.trim();
+
+        if totp::looks_like_code(code) {
+            let row = sqlx::query(
+                "SELECT secret, last_used_step FROM sys_user_totp WHERE user_id = ? AND enabled_at IS NOT NULL"
+            )
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+            let Some(row) = row else {
+                return Ok(false);
+            };
+            let secret = hex::decode(row.get::<String, _>("secret"))
+                .map_err(|e| AppError::Internal(format!("Corrupted TOTP secret: {}", e)))?;
+            let last_used_step: Option<i64> = row.get("last_used_step");
+            let Some(step) = totp::verify(&secret, code, Utc::now().timestamp(), last_used_step) else {
+                return Ok(false);
+            };
+
+            let result = sqlx::query(
+                "UPDATE sys_user_totp SET last_used_step = ?
+                 WHERE user_id = ? AND (last_used_step IS NULL OR last_used_step < ?)"
+            )
+            .bind(step)
+            .bind(user_id)
+            .bind(step)
+            .execute(pool)
+            .await?;
+            return Ok(result.rows_affected() > 0);
+        }
+
+        let result = sqlx::query(
+            "UPDATE sys_user_recovery_code SET used_at = CURRENT_TIMESTAMP
+             WHERE user_id = ? AND code_hash = ? AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .bind(hash_token(&normalize_recovery_code(code)))
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // 用户自行关闭两步验证，需提供有效的验证码或恢复码
+    pub async fn disable(pool: &MySqlPool, user_id: i64, code: &str) -> Result<(), AppError> {
+        if !Self::is_enabled(pool, user_id).await? {
+            return Err(AppError::validation("Two-factor authentication is not enabled"));
+        }
+        if !Self::verify_code(pool, user_id, code).await? {
+            return Err(invalid_code());
+        }
+        Self::remove(pool, user_id).await
+    }
+
+    // 管理员为丢失验证器和恢复码的用户重置两步验证
+    pub async fn reset(pool: &MySqlPool, user_id: i64) -> Result<(), AppError> {
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+
+        if exists == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        Self::remove(pool, user_id).await
+    }
+
+    async fn remove(pool: &MySqlPool, user_id: i64) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM sys_user_totp WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM sys_user_recovery_code WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
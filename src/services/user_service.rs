@@ -0,0 +1,462 @@
+use std::collections::{BTreeSet, HashMap};
+use sqlx::{MySql, MySqlPool, Row, Transaction};
+use crate::error::AppError;
+use crate::models::task::PaginationInfo;
+use crate::models::task_submission::{SubmissionStats, SubmissionStatus};
+use crate::models::user::{
+    AdminUserResponse, RoleSummary, UpdateUserRequest, UserListResponse, UserQuery, UserWithRoles,
+    USER_STATUS_ACTIVE, USER_STATUS_DISABLED,
+};
+use crate::services::auth_service::{is_valid_email, username_problem};
+use crate::services::permission_service::PermissionService;
+use crate::services::token_service::TokenService;
+use crate::services::wallet_service::WalletService;
+use crate::state::AppState;
+
+// 至少保留一个可用的管理员
+const ADMIN_ROLE_KEY: &str = "admin";
+
+const USER_COLUMNS: &str = "u.id, u.username, u.display_name, u.email, u.email_verified_at, u.avatar_url, u.status, u.locked_until,
+    EXISTS(SELECT 1 FROM sys_user_totp t WHERE t.user_id = u.id AND t.enabled_at IS NOT NULL) AS two_factor_enabled,
+    u.created_at, u.updated_at";
+
+fn user_from_row(row: &sqlx::mysql::MySqlRow, roles: Vec<RoleSummary>) -> AdminUserResponse {
+    AdminUserResponse {
+        id: row.get("id"),
+        username: row.get("username"),
+        display_name: row.get("display_name"),
+        email: row.get("email"),
+        email_verified_at: row.get("email_verified_at"),
+        avatar_url: row.get("avatar_url"),
+        status: row.get("status"),
+        locked_until: row.get("locked_until"),
+        two_factor_enabled: row.get("two_factor_enabled"),
+        roles,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+pub struct UserService;
+
+impl UserService {
+    pub async fn list_users(pool: &MySqlPool, query: UserQuery) -> Result<UserListResponse, AppError> {
+        let page = query.page();
+        let page_size = query.page_size();
+        let offset = query.offset();
+
+        // 构建查询条件
+        let mut conditions = Vec::new();
+        let mut search_param = None;
+        if let Some(search) = &query.search
+            && !search.trim().is_empty()
+        {
+            conditions.push("(u.username LIKE ? OR u.email LIKE ?)");
+            search_param = Some(format!("%{}%", search.trim()));
+        }
+        if query.status.is_some() {
+            conditions.push("u.status = ?");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+
+        let count_query = format!("SELECT COUNT(*) FROM sys_user u{}", where_clause);
+        let mut count_sql = sqlx::query_scalar::<_, i64>(&count_query);
+        if let Some(search) = &search_param {
+            count_sql = count_sql.bind(search).bind(search);
+        }
+        if let Some(status) = query.status {
+            count_sql = count_sql.bind(status);
+        }
+        let total = count_sql.fetch_one(pool).await? as u32;
+
+        let data_query = format!(
+            "SELECT {} FROM sys_user u{} ORDER BY u.id DESC LIMIT ? OFFSET ?",
+            USER_COLUMNS, where_clause
+        );
+        let mut data_sql = sqlx::query(&data_query);
+        if let Some(search) = &search_param {
+            data_sql = data_sql.bind(search).bind(search);
+        }
+        if let Some(status) = query.status {
+            data_sql = data_sql.bind(status);
+        }
+        let rows = data_sql.bind(page_size).bind(offset).fetch_all(pool).await?;
+
+        let user_ids: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+        let mut roles = Self::roles_for_users(pool, &user_ids).await?;
+        let users = rows
+            .iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                user_from_row(row, roles.remove(&id).unwrap_or_default())
+            })
+            .collect();
+
+        Ok(UserListResponse {
+            data: users,
+            pagination: PaginationInfo {
+                page,
+                page_size,
+                total,
+                total_pages: total.div_ceil(page_size),
+            },
+        })
+    }
+
+    async fn roles_for_users(pool: &MySqlPool, user_ids: &[i64]) -> Result<HashMap<i64, Vec<RoleSummary>>, AppError> {
+        let mut roles: HashMap<i64, Vec<RoleSummary>> = HashMap::new();
+        if user_ids.is_empty() {
+            return Ok(roles);
+        }
+
+        let placeholders = vec!["?"; user_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT ur.user_id, r.id, r.role_key, r.role_name FROM sys_user_role ur
+             JOIN sys_role r ON ur.role_id = r.id
+             WHERE ur.user_id IN ({}) ORDER BY r.id",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql);
+        for user_id in user_ids {
+            query = query.bind(user_id);
+        }
+
+        for row in query.fetch_all(pool).await? {
+            roles.entry(row.get("user_id")).or_default().push(RoleSummary {
+                id: row.get("id"),
+                role_key: row.get("role_key"),
+                role_name: row.get("role_name"),
+            });
+        }
+        Ok(roles)
+    }
+
+    pub async fn get_user(pool: &MySqlPool, user_id: i64) -> Result<AdminUserResponse, AppError> {
+        let sql = format!("SELECT {} FROM sys_user u WHERE u.id = ?", USER_COLUMNS);
+        let row = sqlx::query(&sql)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+
+        let mut roles = Self::roles_for_users(pool, &[user_id]).await?;
+        Ok(user_from_row(&row, roles.remove(&user_id).unwrap_or_default()))
+    }
+
+    // 当前用户的资料，包括角色、权限、钱包和提交统计
+    pub async fn get_profile(pool: &MySqlPool, user_id: i64) -> Result<UserWithRoles, AppError> {
+        let row = sqlx::query(
+            "SELECT u.id, u.username, u.display_name, u.email, u.email_verified_at, u.avatar_url, u.status, u.created_at,
+                    EXISTS(SELECT 1 FROM sys_user_totp t WHERE t.user_id = u.id AND t.enabled_at IS NOT NULL) AS two_factor_enabled
+             FROM sys_user u WHERE u.id = ?"
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+
+        let roles = PermissionService::get_user_roles(pool, user_id).await?;
+        let mut permissions = PermissionService::get_user_permissions(pool, user_id).await?;
+        permissions.sort();
+        let wallets = WalletService::list_wallets(pool, user_id).await?;
+        let submission_stats = Self::submission_stats(pool, user_id).await?;
+
+        Ok(UserWithRoles {
+            id: row.get("id"),
+            username: row.get("username"),
+            display_name: row.get("display_name"),
+            email: row.get("email"),
+            email_verified_at: row.get("email_verified_at"),
+            avatar_url: row.get("avatar_url"),
+            status: row.get("status"),
+            two_factor_enabled: row.get("two_factor_enabled"),
+            roles,
+            permissions,
+            wallets,
+            submission_stats,
+            created_at: row.get("created_at"),
+        })
+    }
+
+    async fn submission_stats(pool: &MySqlPool, user_id: i64) -> Result<SubmissionStats, AppError> {
+        let rows = sqlx::query("SELECT status, COUNT(*) AS count FROM task_submission WHERE user_id = ? GROUP BY status")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+        let mut stats = SubmissionStats::default();
+        for row in rows {
+            let count: i64 = row.get("count");
+            stats.total += count;
+            let status: String = row.get("status");
+            match SubmissionStatus::parse(&status) {
+                Some(SubmissionStatus::Pending) => stats.pending += count,
+                Some(SubmissionStatus::InReview) => stats.in_review += count,
+                Some(SubmissionStatus::ChangesRequested) => stats.changes_requested += count,
+                Some(SubmissionStatus::Approved) => stats.approved += count,
+                Some(SubmissionStatus::Rejected) => stats.rejected += count,
+                Some(SubmissionStatus::Paid) => stats.paid += count,
+                Some(SubmissionStatus::Withdrawn) => stats.withdrawn += count,
+                None => {}
+            }
+        }
+        Ok(stats)
+    }
+
+    pub async fn update_user(pool: &MySqlPool, user_id: i64, request: UpdateUserRequest) -> Result<AdminUserResponse, AppError> {
+        let mut field_errors = serde_json::Map::new();
+
+        let username = request.username.as_deref().map(str::trim);
+        if let Some(username) = username
+            && let Some(problem) = username_problem(username)
+        {
+            field_errors.insert("username".to_string(), problem.into());
+        }
+        // 空字符串表示清除邮箱
+        let email = request.email.as_deref().map(|email| email.trim().to_lowercase());
+        if let Some(email) = &email
+            && !email.is_empty()
+            && !is_valid_email(email)
+        {
+            field_errors.insert("email".to_string(), "must be a valid email address".into());
+        }
+        if !field_errors.is_empty() {
+            return Err(AppError::validation_with_details("Invalid user", field_errors.into()));
+        }
+
+        let row = sqlx::query("SELECT username, email FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+        let current_email: Option<String> = row.get("email");
+
+        if let Some(username) = username {
+            let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE username = ? AND id <> ?")
+                .bind(username)
+                .bind(user_id)
+                .fetch_one(pool)
+                .await?;
+            if taken > 0 {
+                return Err(AppError::Conflict("The username is already registered".to_string()));
+            }
+
+            sqlx::query("UPDATE sys_user SET username = ? WHERE id = ?")
+                .bind(username)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        }
+
+        // 管理员修改的邮箱视为未验证
+        if let Some(email) = email
+            && current_email.as_deref() != Some(email.as_str())
+        {
+            let email = (!email.is_empty()).then_some(email);
+            if let Some(email) = &email {
+                let taken: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE email = ? AND id <> ?")
+                    .bind(email)
+                    .bind(user_id)
+                    .fetch_one(pool)
+                    .await?;
+                if taken > 0 {
+                    return Err(AppError::Conflict("The email is already registered".to_string()));
+                }
+            }
+
+            sqlx::query("UPDATE sys_user SET email = ?, email_verified_at = NULL WHERE id = ?")
+                .bind(&email)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        }
+
+        Self::get_user(pool, user_id).await
+    }
+
+    // 在事务中校验角色 ID 并写入用户角色
+    pub async fn assign_roles(tx: &mut Transaction<'_, MySql>, user_id: i64, role_ids: &[i64]) -> Result<(), AppError> {
+        let role_ids: BTreeSet<i64> = role_ids.iter().copied().collect();
+
+        if !role_ids.is_empty() {
+            let placeholders = vec!["?"; role_ids.len()].join(", ");
+            let sql = format!("SELECT id FROM sys_role WHERE id IN ({}) FOR SHARE", placeholders);
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for role_id in &role_ids {
+                query = query.bind(role_id);
+            }
+            let existing: BTreeSet<i64> = query.fetch_all(&mut **tx).await?.into_iter().collect();
+
+            let unknown: Vec<i64> = role_ids.difference(&existing).copied().collect();
+            if !unknown.is_empty() {
+                return Err(AppError::validation_with_details(
+                    "Unknown role ids",
+                    serde_json::json!({ "role_ids": unknown }),
+                ));
+            }
+        }
+
+        sqlx::query("DELETE FROM sys_user_role WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        for role_id in role_ids {
+            sqlx::query("INSERT INTO sys_user_role (user_id, role_id) VALUES (?, ?)")
+                .bind(user_id)
+                .bind(role_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // 替换用户的角色；不能移除最后一个可用管理员的 admin 角色
+    pub async fn set_roles(pool: &MySqlPool, user_id: i64, role_ids: &[i64]) -> Result<AdminUserResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE id = ? FOR UPDATE")
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if exists == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+
+        Self::assign_roles(&mut tx, user_id, role_ids).await?;
+        Self::ensure_active_admin(&mut tx).await?;
+
+        tx.commit().await?;
+        Self::get_user(pool, user_id).await
+    }
+
+    // 启用或禁用用户；禁用时吊销该用户的所有会话
+    pub async fn set_enabled(
+        state: &AppState,
+        user_id: i64,
+        acting_user_id: i64,
+        enabled: bool,
+    ) -> Result<AdminUserResponse, AppError> {
+        let pool = &state.pool;
+
+        if !enabled && user_id == acting_user_id {
+            return Err(AppError::validation("You cannot disable your own account"));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let status: Option<i8> = sqlx::query_scalar("SELECT status FROM sys_user WHERE id = ? FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(status) = status else {
+            return Err(AppError::NotFound("User not found".to_string()));
+        };
+
+        if enabled {
+            // 待验证邮箱的账号启用时视为已激活
+            if status != USER_STATUS_ACTIVE {
+                sqlx::query("UPDATE sys_user SET status = ? WHERE id = ?")
+                    .bind(USER_STATUS_ACTIVE)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        } else if status != USER_STATUS_DISABLED {
+            sqlx::query("UPDATE sys_user SET status = ? WHERE id = ?")
+                .bind(USER_STATUS_DISABLED)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+            Self::ensure_active_admin(&mut tx).await?;
+        }
+
+        tx.commit().await?;
+
+        if !enabled {
+            TokenService::revoke_user_sessions(pool, &state.settings.jwt, user_id).await?;
+        }
+        Self::get_user(pool, user_id).await
+    }
+
+    // 删除用户；有发放记录的用户只能禁用
+    pub async fn delete_user(state: &AppState, user_id: i64, acting_user_id: i64) -> Result<(), AppError> {
+        let pool = &state.pool;
+
+        if user_id == acting_user_id {
+            return Err(AppError::validation("You cannot delete your own account"));
+        }
+
+        let payouts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM payout WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        if payouts > 0 {
+            return Err(AppError::Conflict("User has payout records, disable the account instead".to_string()));
+        }
+
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+        if exists == 0 {
+            return Err(AppError::NotFound("User not found".to_string()));
+        }
+        if Self::count_active_admins(pool, Some(user_id)).await? == 0 {
+            return Err(AppError::Conflict("At least one active administrator is required".to_string()));
+        }
+
+        // 删除用户会级联删除 refresh token 记录，需先吊销会话使已签发的 access token 失效
+        TokenService::revoke_user_sessions(pool, &state.settings.jwt, user_id).await?;
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM sys_user WHERE id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        Self::ensure_active_admin(&mut tx).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 可用管理员数量，excluding 用于预先判断删除某个用户后是否还有管理员
+    async fn count_active_admins<'e, E>(executor: E, excluding: Option<i64>) -> Result<i64, AppError>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT u.id) FROM sys_user u
+             JOIN sys_user_role ur ON ur.user_id = u.id
+             JOIN sys_role r ON r.id = ur.role_id
+             WHERE r.role_key = ? AND u.status = ? AND u.id <> COALESCE(?, 0)"
+        )
+        .bind(ADMIN_ROLE_KEY)
+        .bind(USER_STATUS_ACTIVE)
+        .bind(excluding)
+        .fetch_one(executor)
+        .await?;
+        Ok(count)
+    }
+
+    async fn ensure_active_admin(tx: &mut Transaction<'_, MySql>) -> Result<(), AppError> {
+        if Self::count_active_admins(&mut **tx, None).await? == 0 {
+            return Err(AppError::Conflict("At least one active administrator is required".to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::MySqlPool;
+use crate::error::AppError;
+use crate::models::wallet::{BindWalletRequest, UserWallet, WalletChallengeResponse, WalletLoginRequest};
+use crate::services::identity_service::IdentityService;
+use crate::services::login_throttle_service::too_many_requests;
+use crate::utils::sui::{is_valid_sui_address, verify_personal_message};
+use crate::utils::token::generate_token;
+
+// 挑战有效期
+const CHALLENGE_TTL_MINUTES: i64 = 10;
+
+// 未使用的登录挑战数量上限，防止匿名请求写满挑战表
+const MAX_PENDING_LOGIN_CHALLENGES_PER_ADDRESS: i64 = 5;
+const MAX_PENDING_LOGIN_CHALLENGES_PER_IP: i64 = 20;
+
+const WALLET_COLUMNS: &str = "id, user_id, address, is_primary, verified_at, created_at";
+
+pub struct WalletService;
+
+impl WalletService {
+    // 下发一次性挑战消息，钱包签名后用于绑定地址
+    pub async fn create_challenge(pool: &MySqlPool, user_id: i64) -> Result<WalletChallengeResponse, AppError> {
+        let nonce = generate_token(32);
+        let now = Utc::now();
+        let expires_at = now + Duration::minutes(CHALLENGE_TTL_MINUTES);
+        let message = format!(
+            "Learn2Earn wants you to bind this wallet to your account.\n\nUser ID: {}\nNonce: {}\nIssued At: {}",
+            user_id,
+            nonce,
+            now.to_rfc3339()
+        );
+
+        // 顺带清理该用户过期或已使用的挑战
+        sqlx::query("DELETE FROM wallet_challenge WHERE user_id = ? AND (expires_at < CURRENT_TIMESTAMP OR used_at IS NOT NULL)")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        sqlx::query("INSERT INTO wallet_challenge (user_id, nonce, message, expires_at) VALUES (?, ?, ?, ?)")
+            .bind(user_id)
+            .bind(&nonce)
+            .bind(&message)
+            .bind(expires_at)
+            .execute(pool)
+            .await?;
+
+        Ok(WalletChallengeResponse {
+            nonce,
+            message,
+            expires_at,
+        })
+    }
+
+    // 钱包登录的挑战，消息中包含待登录的地址
+    pub async fn create_login_challenge(
+        pool: &MySqlPool,
+        address: &str,
+        client_ip: Option<&str>,
+    ) -> Result<WalletChallengeResponse, AppError> {
+        let address = address.trim().to_ascii_lowercase();
+        if !is_valid_sui_address(&address) {
+            return Err(AppError::validation("address must be a 0x-prefixed 32-byte SUI address"));
+        }
+
+        let nonce = generate_token(32);
+        let now = Utc::now();
+        let expires_at = now + Duration::minutes(CHALLENGE_TTL_MINUTES);
+        let message = format!(
+            "Learn2Earn wants you to sign in with your SUI wallet.\n\nAddress: {}\nNonce: {}\nIssued At: {}",
+            address,
+            nonce,
+            now.to_rfc3339()
+        );
+
+        // 登录挑战不属于任何用户，统一清理过期或已使用的记录
+        sqlx::query("DELETE FROM wallet_challenge WHERE user_id IS NULL AND (expires_at < CURRENT_TIMESTAMP OR used_at IS NOT NULL)")
+            .execute(pool)
+            .await?;
+
+        Self::check_login_challenge_quota(pool, "address", &address, MAX_PENDING_LOGIN_CHALLENGES_PER_ADDRESS).await?;
+        if let Some(ip) = client_ip {
+            Self::check_login_challenge_quota(pool, "client_ip", ip, MAX_PENDING_LOGIN_CHALLENGES_PER_IP).await?;
+        }
+
+        sqlx::query("INSERT INTO wallet_challenge (address, client_ip, nonce, message, expires_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(&address)
+            .bind(client_ip)
+            .bind(&nonce)
+            .bind(&message)
+            .bind(expires_at)
+            .execute(pool)
+            .await?;
+
+        Ok(WalletChallengeResponse {
+            nonce,
+            message,
+            expires_at,
+        })
+    }
+
+    // 未使用的登录挑战达到上限时，等最早的一个过期后才能再创建
+    async fn check_login_challenge_quota(
+        pool: &MySqlPool,
+        column: &str,
+        value: &str,
+        limit: i64,
+    ) -> Result<(), AppError> {
+        let sql = format!(
+            "SELECT COUNT(*), MIN(expires_at) FROM wallet_challenge
+             WHERE user_id IS NULL AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP AND {} = ?",
+            column
+        );
+        let (pending, earliest): (i64, Option<DateTime<Utc>>) = sqlx::query_as(&sql)
+            .bind(value)
+            .fetch_one(pool)
+            .await?;
+
+        if pending >= limit
+            && let Some(until) = earliest
+        {
+            return Err(too_many_requests("Too many pending wallet login challenges, please try again later", until));
+        }
+        Ok(())
+    }
+
+    // 校验登录签名，成功时返回规范化后的地址
+    pub async fn verify_login(pool: &MySqlPool, request: &WalletLoginRequest) -> Result<String, AppError> {
+        let address = request.address.trim().to_ascii_lowercase();
+
+        let message: Option<String> = sqlx::query_scalar(
+            "SELECT message FROM wallet_challenge
+             WHERE nonce = ? AND user_id IS NULL AND address = ? AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP"
+        )
+        .bind(&request.nonce)
+        .bind(&address)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(message) = message else {
+            return Err(AppError::Unauthorized("Invalid or expired nonce".to_string()));
+        };
+
+        let consumed = sqlx::query("UPDATE wallet_challenge SET used_at = CURRENT_TIMESTAMP WHERE nonce = ? AND used_at IS NULL")
+            .bind(&request.nonce)
+            .execute(pool)
+            .await?;
+
+        if consumed.rows_affected() == 0 {
+            return Err(AppError::Unauthorized("Invalid or expired nonce".to_string()));
+        }
+
+        if !verify_personal_message(&address, message.as_bytes(), &request.signature) {
+            return Err(AppError::Unauthorized("Signature does not match the wallet address".to_string()));
+        }
+        Ok(address)
+    }
+
+    pub async fn find_user_by_address(pool: &MySqlPool, address: &str) -> Result<Option<i64>, AppError> {
+        let user_id = sqlx::query_scalar("SELECT user_id FROM user_wallet WHERE address = ?")
+            .bind(address)
+            .fetch_optional(pool)
+            .await?;
+        Ok(user_id)
+    }
+
+    // 校验签名后绑定地址；用户的第一个钱包自动设为默认收款地址
+    pub async fn bind_wallet(
+        pool: &MySqlPool,
+        user_id: i64,
+        request: BindWalletRequest,
+    ) -> Result<UserWallet, AppError> {
+        let address = request.address.trim().to_ascii_lowercase();
+        if !is_valid_sui_address(&address) {
+            return Err(AppError::validation("address must be a 0x-prefixed 32-byte SUI address"));
+        }
+
+        let message: Option<String> = sqlx::query_scalar(
+            "SELECT message FROM wallet_challenge WHERE nonce = ? AND user_id = ? AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP"
+        )
+        .bind(&request.nonce)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(message) = message else {
+            return Err(AppError::validation("Invalid or expired nonce"));
+        };
+
+        // 挑战只能使用一次，签名是否有效都会作废
+        let consumed = sqlx::query("UPDATE wallet_challenge SET used_at = CURRENT_TIMESTAMP WHERE nonce = ? AND used_at IS NULL")
+            .bind(&request.nonce)
+            .execute(pool)
+            .await?;
+
+        if consumed.rows_affected() == 0 {
+            return Err(AppError::validation("Invalid or expired nonce"));
+        }
+
+        if !verify_personal_message(&address, message.as_bytes(), &request.signature) {
+            return Err(AppError::validation("Signature does not match the wallet address"));
+        }
+
+        let owner: Option<i64> = sqlx::query_scalar("SELECT user_id FROM user_wallet WHERE address = ?")
+            .bind(&address)
+            .fetch_optional(pool)
+            .await?;
+
+        let mut tx = pool.begin().await?;
+
+        match owner {
+            Some(owner) if owner != user_id => {
+                return Err(AppError::Conflict("Wallet is already bound to another account".to_string()));
+            }
+            Some(_) => {
+                sqlx::query("UPDATE user_wallet SET verified_at = CURRENT_TIMESTAMP WHERE address = ?")
+                    .bind(&address)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            None => {
+                let has_primary: i64 = sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM user_wallet WHERE user_id = ? AND is_primary = 1 FOR UPDATE"
+                )
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                sqlx::query("INSERT INTO user_wallet (user_id, address, is_primary) VALUES (?, ?, ?)")
+                    .bind(user_id)
+                    .bind(&address)
+                    .bind(has_primary == 0)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        let sql = format!("SELECT {} FROM user_wallet WHERE address = ?", WALLET_COLUMNS);
+        let wallet = sqlx::query_as::<_, UserWallet>(&sql)
+            .bind(&address)
+            .fetch_one(pool)
+            .await?;
+        Ok(wallet)
+    }
+
+    pub async fn list_wallets(pool: &MySqlPool, user_id: i64) -> Result<Vec<UserWallet>, AppError> {
+        let sql = format!(
+            "SELECT {} FROM user_wallet WHERE user_id = ? ORDER BY is_primary DESC, created_at DESC",
+            WALLET_COLUMNS
+        );
+        let wallets = sqlx::query_as::<_, UserWallet>(&sql)
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+        Ok(wallets)
+    }
+
+    pub async fn set_primary(pool: &MySqlPool, user_id: i64, wallet_id: i64) -> Result<UserWallet, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_wallet WHERE id = ? AND user_id = ?")
+            .bind(wallet_id)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if exists == 0 {
+            return Err(AppError::NotFound("Wallet not found".to_string()));
+        }
+
+        sqlx::query("UPDATE user_wallet SET is_primary = (id = ?) WHERE user_id = ?")
+            .bind(wallet_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let sql = format!("SELECT {} FROM user_wallet WHERE id = ?", WALLET_COLUMNS);
+        let wallet = sqlx::query_as::<_, UserWallet>(&sql)
+            .bind(wallet_id)
+            .fetch_one(pool)
+            .await?;
+        Ok(wallet)
+    }
+
+    // 解绑钱包；解绑默认地址时由最近绑定的其他地址接替
+    pub async fn delete_wallet(pool: &MySqlPool, user_id: i64, wallet_id: i64) -> Result<(), AppError> {
+        if !IdentityService::can_remove_sign_in_method(pool, user_id).await? {
+            return Err(AppError::validation("Cannot remove the only sign-in method of this account"));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query("DELETE FROM user_wallet WHERE id = ? AND user_id = ?")
+            .bind(wallet_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Wallet not found".to_string()));
+        }
+
+        let has_primary: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_wallet WHERE user_id = ? AND is_primary = 1")
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if has_primary == 0 {
+            sqlx::query("UPDATE user_wallet SET is_primary = 1 WHERE user_id = ? ORDER BY created_at DESC, id DESC LIMIT 1")
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 奖励发放使用的默认收款地址
+    pub async fn primary_address(pool: &MySqlPool, user_id: i64) -> Result<Option<String>, AppError> {
+        let address = sqlx::query_scalar("SELECT address FROM user_wallet WHERE user_id = ? AND is_primary = 1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(address)
+    }
+}
+
+// 需要 MySQL：L2E_TEST_DATABASE_URL=... cargo test -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{database_pool, unique};
+
+    fn random_address() -> String {
+        format!("0x{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn login_challenges_are_capped_per_address() {
+        let pool = database_pool().await;
+        let address = random_address();
+
+        for _ in 0..MAX_PENDING_LOGIN_CHALLENGES_PER_ADDRESS {
+            WalletService::create_login_challenge(&pool, &address, Some(&unique("ip"))).await.unwrap();
+        }
+        let result = WalletService::create_login_challenge(&pool, &address, Some(&unique("ip"))).await;
+        assert!(matches!(result, Err(AppError::TooManyRequests { .. })));
+
+        // 其他地址不受影响
+        WalletService::create_login_challenge(&pool, &random_address(), None).await.unwrap();
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn login_challenges_are_capped_per_ip() {
+        let pool = database_pool().await;
+        let ip = unique("ip");
+
+        for _ in 0..MAX_PENDING_LOGIN_CHALLENGES_PER_IP {
+            WalletService::create_login_challenge(&pool, &random_address(), Some(&ip)).await.unwrap();
+        }
+        let result = WalletService::create_login_challenge(&pool, &random_address(), Some(&ip)).await;
+        assert!(matches!(result, Err(AppError::TooManyRequests { .. })));
+
+        WalletService::create_login_challenge(&pool, &random_address(), Some(&unique("ip"))).await.unwrap();
+    }
+}
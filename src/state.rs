@@ -0,0 +1,69 @@
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use crate::config::settings::Settings;
+use crate::error::AppError;
+use crate::mailer::{self, Mailer};
+use crate::oauth::github::GithubOAuthClient;
+use crate::payout::PayoutClient;
+use crate::services::permission_service::PermissionCache;
+use crate::utils::jwt::JwtService;
+
+// 应用共享状态：启动时创建一次，所有 worker 通过 web::Data<AppState> 共享。
+// 字段均为公开的，测试中可直接构造并替换 mailer、payout_client 等实现
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub settings: Settings,
+    pub jwt: JwtService,
+    pub mailer: Arc<dyn Mailer>,
+    pub payout_client: Option<PayoutClient>, // 仅在 payout.enabled 时创建
+    pub github_client: Option<GithubOAuthClient>, // 仅在 oauth.github.enabled 时创建
+    pub permission_cache: PermissionCache,
+}
+
+impl AppState {
+    // 按配置创建各组件，任一组件初始化失败时拒绝启动
+    pub fn from_settings(pool: MySqlPool, settings: Settings) -> Result<Self, String> {
+        let jwt = JwtService::from_settings(&settings.jwt)?;
+
+        let payout_client = if settings.payout.enabled {
+            let client = PayoutClient::from_settings(&settings.payout)
+                .map_err(|e| format!("failed to initialize payout client: {}", e))?;
+            Some(client)
+        } else {
+            None
+        };
+
+        let github_client = settings
+            .oauth
+            .github
+            .enabled
+            .then(|| GithubOAuthClient::new(&settings.oauth.github));
+
+        Ok(AppState {
+            pool,
+            jwt,
+            mailer: mailer::from_settings(&settings.mail),
+            payout_client,
+            github_client,
+            permission_cache: PermissionCache::default(),
+            settings,
+        })
+    }
+
+    pub fn payout_client(&self) -> Result<&PayoutClient, AppError> {
+        self.payout_client
+            .as_ref()
+            .ok_or_else(|| AppError::Conflict("Payouts are disabled".to_string()))
+    }
+
+    pub fn github_client(&self) -> Result<&GithubOAuthClient, AppError> {
+        self.github_client
+            .as_ref()
+            .ok_or_else(|| AppError::NotFound("GitHub login is not enabled".to_string()))
+    }
+
+    // 邮件中链接使用的对外地址，不带末尾的 /
+    pub fn public_url(&self) -> &str {
+        self.settings.server.public_url.trim_end_matches('/')
+    }
+}
@@ -0,0 +1,117 @@
+// 测试辅助：构造注入用的 AppState 和需要 MySQL 的测试使用的数据库
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::MySqlPool;
+use crate::config::settings::{RunMode, Settings};
+use crate::database::migration::run_migrations;
+use crate::mailer;
+use crate::services::permission_service::PermissionCache;
+use crate::state::AppState;
+use crate::utils::jwt::JwtService;
+use crate::utils::jwt_keys::JwtKeyRing;
+
+// 需要数据库的测试标记为 #[ignore]，指向一个可随意写入的库后用 cargo test -- --ignored 运行
+const TEST_DATABASE_ENV: &str = "L2E_TEST_DATABASE_URL";
+
+pub const TEST_JWT_SECRET: &str = "test-secret";
+
+// 不会实际建立连接的连接池，用于不访问数据库的 handler 测试
+pub fn lazy_pool() -> MySqlPool {
+    MySqlPoolOptions::new()
+        .connect_lazy("mysql://test@127.0.0.1:1/unused")
+        .expect("valid database url")
+}
+
+pub async fn database_pool() -> MySqlPool {
+    let url = std::env::var(TEST_DATABASE_ENV)
+        .unwrap_or_else(|_| panic!("{} must point to a disposable MySQL database", TEST_DATABASE_ENV));
+    let pool = MySqlPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("failed to connect to the test database");
+    run_migrations(&pool).await.expect("failed to migrate the test database");
+    pool
+}
+
+// HS256 密钥、日志邮件，不启用出款和 GitHub 登录；字段可按需替换
+pub fn test_state(pool: MySqlPool) -> AppState {
+    let settings = Settings {
+        mode: RunMode::Dev,
+        ..Settings::default()
+    };
+
+    AppState {
+        pool,
+        jwt: JwtService::new(JwtKeyRing::hmac(TEST_JWT_SECRET), settings.jwt.access_ttl_minutes),
+        mailer: mailer::from_settings(&settings.mail),
+        payout_client: None,
+        github_client: None,
+        permission_cache: PermissionCache::default(),
+        settings,
+    }
+}
+
+// 唯一后缀，避免测试数据互相冲突
+pub fn unique(prefix: &str) -> String {
+    format!("{}_{}", prefix, &uuid::Uuid::new_v4().simple().to_string()[..16])
+}
+
+pub async fn insert_user(pool: &MySqlPool) -> i64 {
+    sqlx::query("INSERT INTO sys_user (username, password_hash) VALUES (?, '')")
+        .bind(unique("user"))
+        .execute(pool)
+        .await
+        .expect("failed to insert test user")
+        .last_insert_id() as i64
+}
+
+// 在新建的已发布任务下插入指定状态的提交，返回提交 ID
+pub async fn insert_submission(pool: &MySqlPool, user_id: i64, status: &str) -> i64 {
+    let task_id = sqlx::query(
+        "INSERT INTO task (code, name, reward_cny, description, status) VALUES (?, 'test task', 10, '', 'published')"
+    )
+    .bind(unique("task"))
+    .execute(pool)
+    .await
+    .expect("failed to insert test task")
+    .last_insert_id();
+    sqlx::query(
+        "INSERT INTO task_submission (task_id, user_id, pr_url, status) VALUES (?, ?, 'https://github.com/example/repo/pull/1', ?)"
+    )
+    .bind(task_id)
+    .bind(user_id)
+    .bind(status)
+    .execute(pool)
+    .await
+    .expect("failed to insert test submission")
+    .last_insert_id() as i64
+}
+
+// 通过新建角色授予用户权限，返回角色 ID
+pub async fn grant_permission(pool: &MySqlPool, user_id: i64, perm_key: &str) -> i64 {
+    sqlx::query("INSERT IGNORE INTO sys_permission (perm_key, perm_name) VALUES (?, ?)")
+        .bind(perm_key)
+        .bind(perm_key)
+        .execute(pool)
+        .await
+        .expect("failed to insert test permission");
+    let role_id = sqlx::query("INSERT INTO sys_role (role_key, role_name) VALUES (?, 'test role')")
+        .bind(unique("role"))
+        .execute(pool)
+        .await
+        .expect("failed to insert test role")
+        .last_insert_id() as i64;
+    sqlx::query("INSERT INTO sys_role_perm (role_id, perm_id) SELECT ?, id FROM sys_permission WHERE perm_key = ?")
+        .bind(role_id)
+        .bind(perm_key)
+        .execute(pool)
+        .await
+        .expect("failed to grant test permission");
+    sqlx::query("INSERT INTO sys_user_role (user_id, role_id) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(role_id)
+        .execute(pool)
+        .await
+        .expect("failed to assign test role");
+    role_id
+}
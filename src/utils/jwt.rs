@@ -1,72 +1,327 @@
-use actix_web::{dev::ServiceRequest, Error, HttpMessage};
+use actix_web::{dev::{Payload, ServiceRequest}, web, Error, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::{ready, Ready};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use jsonwebtoken::TokenData;
 use serde::{Deserialize, Serialize};
 use chrono::{Duration, Utc};
-use std::env;
+use crate::config::settings::JwtSettings;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::utils::jwt_keys::JwtKeyRing;
+use crate::services::personal_token_service::{PersonalTokenService, PERSONAL_TOKEN_PREFIX};
+use crate::services::session_service::SessionService;
+use crate::services::token_service::TokenService;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
-    pub sub: String,  // 用户ID
-    pub role: String, // 用户角色
-    pub exp: i64,     // 过期时间
-    pub iat: i64,     // 签发时间
+    pub sub: String,        // 用户ID
+    pub roles: Vec<String>, // 用户角色编码
+    pub perms: Vec<String>, // 用户权限编码
+    pub exp: i64,           // 过期时间
+    pub iat: i64,           // 签发时间
+    pub jti: String,        // 令牌ID，用于吊销
+    // 所属会话（refresh token family），会话被吊销后令牌随即失效
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub sid: String,
+    // 策略要求启用两步验证但用户尚未启用：令牌只能用于完成 2FA 设置，不带任何角色和权限
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub two_factor_pending: bool,
+    // 使用个人访问令牌认证时为令牌 ID，perms 为令牌范围；不出现在 JWT 中
+    #[serde(skip)]
+    pub personal_token_id: Option<i64>,
 }
 
-pub struct JwtService;
+impl Claims {
+    pub fn user_id(&self) -> Result<i64, AppError> {
+        self.sub
+            .parse()
+            .map_err(|_| AppError::Unauthorized("Invalid token subject".to_string()))
+    }
+}
+
+// 在受保护路由的 handler 中直接提取当前用户声明
+impl FromRequest for Claims {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            req.extensions()
+                .get::<Claims>()
+                .cloned()
+                .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string())),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct JwtService {
+    key_ring: JwtKeyRing,
+    access_ttl_minutes: i64,
+}
 
 impl JwtService {
-    pub fn new() -> Self {
-        JwtService
+    pub fn new(key_ring: JwtKeyRing, access_ttl_minutes: i64) -> Self {
+        JwtService { key_ring, access_ttl_minutes }
+    }
+
+    // 启动时读取一次密钥：未配置 key_dir 时使用 HS256 密钥
+    pub fn from_settings(settings: &JwtSettings) -> Result<Self, String> {
+        let key_ring = if settings.key_dir.is_empty() {
+            JwtKeyRing::hmac(&settings.secret)
+        } else {
+            JwtKeyRing::load_dir(&settings.key_dir, &settings.signing_kid)?
+        };
+        Ok(JwtService::new(key_ring, settings.access_ttl_minutes))
     }
 
-    pub fn create_token(&self, user_id: &str, role: &str) -> Result<String, jsonwebtoken::errors::Error> {
-        let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key".to_string());
+    pub fn create_token(
+        &self,
+        user_id: &str,
+        roles: &[String],
+        perms: &[String],
+        jti: &str,
+        sid: &str,
+        two_factor_pending: bool,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
-        let expire = now + Duration::hours(24); // 24小时过期
+        let expire = now + Duration::minutes(self.access_ttl_minutes);
 
         let claims = Claims {
             sub: user_id.to_owned(),
-            role: role.to_owned(),
+            roles: roles.to_vec(),
+            perms: perms.to_vec(),
             exp: expire.timestamp(),
             iat: now.timestamp(),
+            jti: jti.to_owned(),
+            sid: sid.to_owned(),
+            two_factor_pending,
+            personal_token_id: None,
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(secret.as_ref()),
-        )
+        self.key_ring.sign(&claims)
     }
 
     pub fn verify_token(&self, token: &str) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
-        let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "your-secret-key".to_string());
-        
-        decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_ref()),
-            &Validation::default(),
-        )
+        self.key_ring.verify(token)
+    }
+
+    pub fn jwks(&self) -> serde_json::Value {
+        self.key_ring.jwks()
     }
 }
 
+// 尚未按策略启用两步验证的令牌只能访问 2FA 设置接口
+const TWO_FACTOR_SETUP_PATHS: &[&str] = &["/api/me/2fa", "/api/me/2fa/setup", "/api/me/2fa/verify"];
+
+fn allowed_while_two_factor_pending(path: &str) -> bool {
+    TWO_FACTOR_SETUP_PATHS.contains(&path.trim_end_matches('/'))
+}
+
 // JWT 验证中间件
 pub async fn jwt_validator(
     req: ServiceRequest,
     credentials: BearerAuth,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
-    let jwt_service = JwtService::new();
+    let state = req
+        .app_data::<web::Data<AppState>>()
+        .cloned()
+        .expect("AppState must be registered as app data");
     let token = credentials.token();
 
-    match jwt_service.verify_token(token) {
+    if token.starts_with(PERSONAL_TOKEN_PREFIX) {
+        return personal_token_validator(req, &state, token).await;
+    }
+
+    match state.jwt.verify_token(token) {
         Ok(token_data) => {
+            // 检查令牌是否已被吊销（登出或 refresh token 重放）
+            match TokenService::is_access_token_revoked(&state.pool, &token_data.claims.jti).await {
+                Ok(false) => {}
+                Ok(true) => {
+                    let error = AppError::Unauthorized("Token revoked".to_string());
+                    return Err((error.into(), req));
+                }
+                Err(e) => return Err((e.into(), req)),
+            }
+
+            // 会话被远程登出后拒绝其令牌，同时记录最近活跃时间
+            if !token_data.claims.sid.is_empty() {
+                match SessionService::touch(&state.pool, &token_data.claims.sid).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        let error = AppError::Unauthorized("Session revoked".to_string());
+                        return Err((error.into(), req));
+                    }
+                    Err(e) => return Err((e.into(), req)),
+                }
+            }
+
+            if token_data.claims.two_factor_pending && !allowed_while_two_factor_pending(req.path()) {
+                let error = AppError::Forbidden("Two-factor authentication must be set up first".to_string());
+                return Err((error.into(), req));
+            }
+
             // 将用户信息添加到请求扩展中
             req.extensions_mut().insert(token_data.claims);
             Ok(req)
         }
         Err(_) => {
-            let error = actix_web::error::ErrorUnauthorized("Invalid token");
-            Err((error, req))
+            let error = AppError::Unauthorized("Invalid token".to_string());
+            Err((error.into(), req))
+        }
+    }
+}
+
+// 个人访问令牌认证：不带角色，权限为令牌范围（实际生效时再与用户当前权限取交集）
+async fn personal_token_validator(
+    req: ServiceRequest,
+    state: &AppState,
+    token: &str,
+) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    match PersonalTokenService::authenticate(&state.pool, token).await {
+        Ok(Some(authenticated)) => {
+            let now = Utc::now().timestamp();
+            req.extensions_mut().insert(Claims {
+                sub: authenticated.user_id.to_string(),
+                roles: Vec::new(),
+                perms: authenticated.scopes,
+                exp: now,
+                iat: now,
+                jti: format!("pat-{}", authenticated.token_id),
+                sid: String::new(),
+                two_factor_pending: false,
+                personal_token_id: Some(authenticated.token_id),
+            });
+            Ok(req)
         }
+        Ok(None) => {
+            let error = AppError::Unauthorized("Invalid token".to_string());
+            Err((error.into(), req))
+        }
+        Err(e) => Err((e.into(), req)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App};
+    use actix_web_httpauth::middleware::HttpAuthentication;
+    use super::*;
+    use crate::routes::auth_routes::configure_protected_auth_routes;
+    use crate::routes::me_routes::configure_me_routes;
+    use crate::test_support::{database_pool, insert_user, lazy_pool, test_state, TEST_JWT_SECRET};
+
+    // 与 main 中相同的受保护路由装配
+    async fn get_profile(state: AppState, token: Option<&str>) -> actix_web::dev::ServiceResponse {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state))
+                .service(
+                    web::scope("/api")
+                        .wrap(HttpAuthentication::bearer(jwt_validator))
+                        .configure(configure_protected_auth_routes),
+                ),
+        )
+        .await;
+
+        let mut request = test::TestRequest::get().uri("/api/auth/profile");
+        if let Some(token) = token {
+            request = request.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        test::call_service(&app, request.to_request()).await
     }
-}
\ No newline at end of file
+
+    fn token_from(service: &JwtService) -> String {
+        service.create_token("1", &[], &[], "jti", "", false).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn two_factor_pending_is_limited_to_setup() {
+        assert!(allowed_while_two_factor_pending("/api/me/2fa"));
+        assert!(allowed_while_two_factor_pending("/api/me/2fa/setup"));
+        assert!(allowed_while_two_factor_pending("/api/me/2fa/verify/"));
+        assert!(!allowed_while_two_factor_pending("/api/me/2fa/disable"));
+        assert!(!allowed_while_two_factor_pending("/api/me"));
+        assert!(!allowed_while_two_factor_pending("/api/submissions"));
+        assert!(!allowed_while_two_factor_pending("/api/me/2fa/setup/../../wallets"));
+    }
+
+    #[actix_web::test]
+    async fn missing_token_is_unauthorized() {
+        let response = get_profile(test_state(lazy_pool()), None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn malformed_token_is_unauthorized() {
+        let response = get_profile(test_state(lazy_pool()), Some("not-a-jwt")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["message"], AppError::Unauthorized("Invalid token".to_string()).to_string());
+    }
+
+    #[actix_web::test]
+    async fn token_signed_with_another_key_is_unauthorized() {
+        let other = JwtService::new(JwtKeyRing::hmac("another-secret"), 15);
+        let response = get_profile(test_state(lazy_pool()), Some(&token_from(&other))).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn expired_token_is_unauthorized() {
+        let expired = JwtService::new(JwtKeyRing::hmac(TEST_JWT_SECRET), -10);
+        let response = get_profile(test_state(lazy_pool()), Some(&token_from(&expired))).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn valid_token_reaches_handler() {
+        let state = test_state(database_pool().await);
+        let user_id = insert_user(&state.pool).await;
+        let token = state
+            .jwt
+            .create_token(&user_id.to_string(), &[], &[], &uuid::Uuid::new_v4().to_string(), "", false)
+            .unwrap();
+
+        let response = get_profile(state, Some(&token)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires L2E_TEST_DATABASE_URL"]
+    async fn two_factor_pending_token_only_reaches_setup() {
+        let state = web::Data::new(test_state(database_pool().await));
+        let user_id = insert_user(&state.pool).await;
+        let jti = uuid::Uuid::new_v4().to_string();
+        let token = state.jwt.create_token(&user_id.to_string(), &[], &[], &jti, "", true).unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(state.clone())
+                .service(
+                    web::scope("/api")
+                        .wrap(HttpAuthentication::bearer(jwt_validator))
+                        .configure(configure_protected_auth_routes)
+                        .configure(configure_me_routes),
+                ),
+        )
+        .await;
+
+        for (uri, expected) in [
+            ("/api/me/2fa", StatusCode::OK),
+            ("/api/me", StatusCode::FORBIDDEN),
+            ("/api/me/wallets", StatusCode::FORBIDDEN),
+            ("/api/auth/profile", StatusCode::FORBIDDEN),
+        ] {
+            let request = test::TestRequest::get()
+                .uri(uri)
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), expected, "GET {}", uri);
+        }
+    }
+}
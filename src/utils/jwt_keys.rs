@@ -0,0 +1,295 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use jsonwebtoken::errors::{Error, ErrorKind};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::traits::PublicKeyParts;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+struct JwtKey {
+    kid: Option<String>, // 对称密钥没有 kid
+    algorithm: Algorithm,
+    encoding: Option<EncodingKey>, // 只有公钥的密钥仅用于验证
+    decoding: DecodingKey,
+    jwk: Option<serde_json::Value>,
+}
+
+// 签名和验证 JWT 的密钥集合：使用 signing_kid 签名，集合中的所有密钥都可用于验证
+#[derive(Default)]
+pub struct JwtKeyRing {
+    keys: Vec<JwtKey>,
+    signing: Option<usize>,
+}
+
+// 不输出密钥内容
+impl fmt::Debug for JwtKeyRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKeyRing")
+            .field("kids", &self.keys.iter().map(|key| key.kid.as_deref()).collect::<Vec<_>>())
+            .field("signing", &self.signing.map(|index| self.keys[index].kid.as_deref()))
+            .finish()
+    }
+}
+
+impl JwtKeyRing {
+    // HS256：所有验证方都需要持有同一个密钥
+    pub fn hmac(secret: &str) -> Self {
+        JwtKeyRing {
+            keys: vec![JwtKey {
+                kid: None,
+                algorithm: Algorithm::HS256,
+                encoding: Some(EncodingKey::from_secret(secret.as_bytes())),
+                decoding: DecodingKey::from_secret(secret.as_bytes()),
+                jwk: None,
+            }],
+            signing: Some(0),
+        }
+    }
+
+    // 读取目录下的 <kid>.pem：私钥可签名和验证，公钥只用于验证已签发的令牌（轮换后保留到令牌过期）
+    pub fn load_dir(dir: &str, signing_kid: &str) -> Result<Self, String> {
+        let entries = fs::read_dir(dir).map_err(|e| format!("failed to read jwt.key_dir '{}': {}", dir, e))?;
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pem"))
+            .collect();
+        paths.sort();
+
+        let mut keys = Vec::new();
+        for path in paths {
+            keys.push(load_key(&path)?);
+        }
+
+        let signing = keys
+            .iter()
+            .position(|key| key.kid.as_deref() == Some(signing_kid))
+            .ok_or_else(|| format!("jwt.signing_kid '{}' was not found in {}", signing_kid, dir))?;
+        if keys[signing].encoding.is_none() {
+            return Err(format!("jwt.signing_kid '{}' must be a private key", signing_kid));
+        }
+
+        Ok(JwtKeyRing { keys, signing: Some(signing) })
+    }
+
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, Error> {
+        let key = self.signing.map(|index| &self.keys[index]);
+        let Some(JwtKey { kid, algorithm, encoding: Some(encoding), .. }) = key else {
+            return Err(ErrorKind::InvalidKeyFormat.into());
+        };
+
+        let mut header = Header::new(*algorithm);
+        header.kid = kid.clone();
+        encode(&header, claims, encoding)
+    }
+
+    // 按令牌头中的 kid 选择密钥，算法以密钥为准，不信任令牌头中的 alg
+    pub fn verify<T: DeserializeOwned>(&self, token: &str) -> Result<TokenData<T>, Error> {
+        let header = decode_header(token)?;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.kid == header.kid)
+            .ok_or_else(|| Error::from(ErrorKind::InvalidToken))?;
+
+        decode::<T>(token, &key.decoding, &Validation::new(key.algorithm))
+    }
+
+    // 公开的 JWK Set，供其他服务验证令牌；对称密钥不公开
+    pub fn jwks(&self) -> serde_json::Value {
+        let keys: Vec<&serde_json::Value> = self.keys.iter().filter_map(|key| key.jwk.as_ref()).collect();
+        serde_json::json!({ "keys": keys })
+    }
+}
+
+fn load_key(path: &Path) -> Result<JwtKey, String> {
+    let kid = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| format!("invalid key file name: {}", path.display()))?
+        .to_string();
+    let pem = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let invalid = |e: &dyn fmt::Display| format!("invalid key {}: {}", path.display(), e);
+
+    // RSA 私钥（PKCS#1 / PKCS#8）
+    let rsa_private = RsaPrivateKey::from_pkcs1_pem(&pem)
+        .ok()
+        .or_else(|| RsaPrivateKey::from_pkcs8_pem(&pem).ok());
+    if let Some(private_key) = rsa_private {
+        let encoding = EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| invalid(&e))?;
+        return rsa_key(kid, &private_key.to_public_key(), Some(encoding)).map_err(|e| invalid(&e));
+    }
+
+    // RSA 公钥（PKCS#1 / SPKI）
+    let rsa_public = RsaPublicKey::from_pkcs1_pem(&pem)
+        .ok()
+        .or_else(|| RsaPublicKey::from_public_key_pem(&pem).ok());
+    if let Some(public_key) = rsa_public {
+        return rsa_key(kid, &public_key, None).map_err(|e| invalid(&e));
+    }
+
+    // Ed25519 私钥（PKCS#8）
+    if let Ok(signing_key) = SigningKey::from_pkcs8_pem(&pem) {
+        let encoding = EncodingKey::from_ed_pem(pem.as_bytes()).map_err(|e| invalid(&e))?;
+        return ed25519_key(kid, &signing_key.verifying_key(), Some(encoding)).map_err(|e| invalid(&e));
+    }
+
+    // Ed25519 公钥（SPKI）
+    if let Ok(verifying_key) = VerifyingKey::from_public_key_pem(&pem) {
+        return ed25519_key(kid, &verifying_key, None).map_err(|e| invalid(&e));
+    }
+
+    Err(invalid(&"expected an RSA or Ed25519 key in PEM format"))
+}
+
+fn rsa_key(kid: String, public_key: &RsaPublicKey, encoding: Option<EncodingKey>) -> Result<JwtKey, Error> {
+    let n = URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be());
+    let e = URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be());
+
+    Ok(JwtKey {
+        decoding: DecodingKey::from_rsa_components(&n, &e)?,
+        jwk: Some(serde_json::json!({
+            "kty": "RSA",
+            "use": "sig",
+            "alg": "RS256",
+            "kid": kid,
+            "n": n,
+            "e": e,
+        })),
+        kid: Some(kid),
+        algorithm: Algorithm::RS256,
+        encoding,
+    })
+}
+
+fn ed25519_key(kid: String, public_key: &VerifyingKey, encoding: Option<EncodingKey>) -> Result<JwtKey, Error> {
+    let x = URL_SAFE_NO_PAD.encode(public_key.as_bytes());
+
+    Ok(JwtKey {
+        decoding: DecodingKey::from_ed_components(&x)?,
+        jwk: Some(serde_json::json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "use": "sig",
+            "alg": "EdDSA",
+            "kid": kid,
+            "x": x,
+        })),
+        kid: Some(kid),
+        algorithm: Algorithm::EdDSA,
+        encoding,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    // tests/fixtures/jwt 下的密钥仅用于测试
+    fn fixture(dir: &str) -> String {
+        format!("{}/tests/fixtures/jwt/{}", env!("CARGO_MANIFEST_DIR"), dir)
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestClaims {
+        sub: String,
+        exp: i64,
+    }
+
+    fn claims() -> TestClaims {
+        TestClaims {
+            sub: "42".to_string(),
+            exp: chrono::Utc::now().timestamp() + 600,
+        }
+    }
+
+    fn assert_round_trip(ring: &JwtKeyRing, kid: &str, algorithm: Algorithm) {
+        let token = ring.sign(&claims()).unwrap();
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.kid.as_deref(), Some(kid));
+        assert_eq!(header.alg, algorithm);
+        assert_eq!(ring.verify::<TestClaims>(&token).unwrap().claims.sub, "42");
+    }
+
+    #[test]
+    fn signs_and_verifies_rs256() {
+        let ring = JwtKeyRing::load_dir(&fixture("rs256"), "2025-08").unwrap();
+        assert_round_trip(&ring, "2025-08", Algorithm::RS256);
+    }
+
+    #[test]
+    fn signs_and_verifies_eddsa() {
+        let ring = JwtKeyRing::load_dir(&fixture("eddsa"), "2025-09").unwrap();
+        assert_round_trip(&ring, "2025-09", Algorithm::EdDSA);
+    }
+
+    #[test]
+    fn old_kid_still_verifies_after_rotation() {
+        let old_ring = JwtKeyRing::load_dir(&fixture("rs256"), "2025-08").unwrap();
+        let old_token = old_ring.sign(&claims()).unwrap();
+
+        // 轮换后旧密钥只保留公钥，新令牌使用新的 kid 签名
+        let rotated = JwtKeyRing::load_dir(&fixture("rotated"), "2025-09").unwrap();
+        assert_eq!(rotated.verify::<TestClaims>(&old_token).unwrap().claims.sub, "42");
+        assert_round_trip(&rotated, "2025-09", Algorithm::EdDSA);
+    }
+
+    #[test]
+    fn public_only_key_cannot_sign() {
+        let error = JwtKeyRing::load_dir(&fixture("rotated"), "2025-08").unwrap_err();
+        assert!(error.contains("must be a private key"), "{}", error);
+        assert!(JwtKeyRing::load_dir(&fixture("rotated"), "2024-01").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_kid() {
+        let token = JwtKeyRing::load_dir(&fixture("eddsa"), "2025-09").unwrap().sign(&claims()).unwrap();
+        let ring = JwtKeyRing::load_dir(&fixture("rs256"), "2025-08").unwrap();
+        assert!(ring.verify::<TestClaims>(&token).is_err());
+
+        // 没有 kid 的 HS256 令牌也不能通过非对称密钥集合的验证
+        let hmac_token = JwtKeyRing::hmac("secret").sign(&claims()).unwrap();
+        assert!(ring.verify::<TestClaims>(&hmac_token).is_err());
+    }
+
+    #[test]
+    fn rejects_token_with_forged_kid() {
+        // 用其他密钥签名但声称是已知的 kid
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some("2025-08".to_string());
+        let other = fs::read(format!("{}/2025-09.pem", fixture("eddsa"))).unwrap();
+        let token = encode(&header, &claims(), &EncodingKey::from_ed_pem(&other).unwrap()).unwrap();
+
+        let ring = JwtKeyRing::load_dir(&fixture("rotated"), "2025-09").unwrap();
+        assert!(ring.verify::<TestClaims>(&token).is_err());
+    }
+
+    #[test]
+    fn jwks_contains_only_public_parts() {
+        let ring = JwtKeyRing::load_dir(&fixture("rotated"), "2025-09").unwrap();
+        let jwks = ring.jwks();
+        let keys = jwks["keys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+
+        for key in keys {
+            let mut fields: Vec<&str> = key.as_object().unwrap().keys().map(String::as_str).collect();
+            fields.sort();
+            match key["kty"].as_str().unwrap() {
+                "RSA" => assert_eq!(fields, ["alg", "e", "kid", "kty", "n", "use"]),
+                "OKP" => assert_eq!(fields, ["alg", "crv", "kid", "kty", "use", "x"]),
+                other => panic!("unexpected key type {}", other),
+            }
+        }
+
+        // 对称密钥不公开
+        assert_eq!(JwtKeyRing::hmac("secret").jwks(), serde_json::json!({ "keys": [] }));
+    }
+}
@@ -1 +1,5 @@
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod token;
+pub mod sui;
+pub mod totp;
+pub mod jwt_keys;
\ No newline at end of file
@@ -0,0 +1,148 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use ed25519_dalek::{Signature, VerifyingKey};
+
+pub type Blake2b256 = Blake2b<U32>;
+
+// SUI 签名方案标识
+pub const ED25519_FLAG: u8 = 0x00;
+
+// 个人消息签名使用的 intent 前缀：PersonalMessage / V0 / Sui
+const PERSONAL_MESSAGE_INTENT: [u8; 3] = [3, 0, 0];
+
+// SUI 地址 = blake2b256(flag || public_key)
+pub fn sui_address_from_public_key(public_key: &VerifyingKey) -> String {
+    let mut hasher = Blake2b256::new();
+    hasher.update([ED25519_FLAG]);
+    hasher.update(public_key.as_bytes());
+    format!("0x{}", hex::encode(hasher.finalize()))
+}
+
+// 0x 开头的 32 字节十六进制地址
+pub fn is_valid_sui_address(address: &str) -> bool {
+    address
+        .strip_prefix("0x")
+        .is_some_and(|hex_part| hex_part.len() == 64 && hex_part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// 校验钱包 signPersonalMessage 的签名（base64(flag || signature || public_key)），
+// 目前只支持 Ed25519 账户
+pub fn verify_personal_message(address: &str, message: &[u8], signature: &str) -> bool {
+    let Ok(bytes) = BASE64.decode(signature.trim()) else {
+        return false;
+    };
+    let [ED25519_FLAG, rest @ ..] = bytes.as_slice() else {
+        return false;
+    };
+    if rest.len() != 64 + 32 {
+        return false;
+    }
+    let (signature_bytes, public_key_bytes) = rest.split_at(64);
+
+    let Ok(public_key_bytes) = <[u8; 32]>::try_from(public_key_bytes) else {
+        return false;
+    };
+    let Ok(public_key) = VerifyingKey::from_bytes(&public_key_bytes) else {
+        return false;
+    };
+    if !sui_address_from_public_key(&public_key).eq_ignore_ascii_case(address) {
+        return false;
+    }
+    let Ok(signature) = Signature::from_slice(signature_bytes) else {
+        return false;
+    };
+
+    // 签名对象为 blake2b256(intent || bcs(message))，bcs 对字节数组使用 ULEB128 长度前缀
+    let mut hasher = Blake2b256::new();
+    hasher.update(PERSONAL_MESSAGE_INTENT);
+    hasher.update(uleb128(message.len()));
+    hasher.update(message);
+    let digest = hasher.finalize();
+
+    public_key.verify_strict(&digest, &signature).is_ok()
+}
+
+fn uleb128(mut value: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8032 7.1 TEST 1 的密钥，签名由独立实现按 SUI 个人消息格式生成
+    const ADDRESS: &str = "0x304af458e90e97c841685b8cbbc59b909f3e2cf150df590ada4c81452c29737d";
+    const MESSAGE: &[u8] = b"Sign in to Learn2Earn\nNonce: 3f2a9c";
+    const SIGNATURE: &str = "AGb7amLhC08yualft5HHWDQIM8oWk3VRmLcesw0nXb7tVo7vqoORLIk8PrSc3TD61RUOeAosXKc6ezWdJOck7QjXWpgBgrEKt9VL/tPJZAc6DuFy89qmIyWvAhpo9wdRGg==";
+    // 200 字节的消息，长度前缀占两个字节
+    const LONG_SIGNATURE: &str = "ACD+CsZr1wyuBpMKDGqfWjy597Vktz3y+ywv2mPbgcl249/aONd56dHYXayA9vz9vpFOLODlW2VTRtaqu59FAALXWpgBgrEKt9VL/tPJZAc6DuFy89qmIyWvAhpo9wdRGg==";
+
+    fn modified_signature(modify: impl FnOnce(&mut Vec<u8>)) -> String {
+        let mut bytes = BASE64.decode(SIGNATURE).unwrap();
+        modify(&mut bytes);
+        BASE64.encode(bytes)
+    }
+
+    #[test]
+    fn address_is_derived_from_public_key() {
+        let public_key = hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let public_key = VerifyingKey::from_bytes(&public_key.try_into().unwrap()).unwrap();
+        assert_eq!(sui_address_from_public_key(&public_key), ADDRESS);
+        assert!(is_valid_sui_address(ADDRESS));
+    }
+
+    #[test]
+    fn accepts_known_good_signature() {
+        assert!(verify_personal_message(ADDRESS, MESSAGE, SIGNATURE));
+        assert!(verify_personal_message(&ADDRESS.to_uppercase().replacen("0X", "0x", 1), MESSAGE, SIGNATURE));
+        assert!(verify_personal_message(ADDRESS, &[b'x'; 200], LONG_SIGNATURE));
+    }
+
+    #[test]
+    fn rejects_wrong_address() {
+        let other = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        assert!(!verify_personal_message(other, MESSAGE, SIGNATURE));
+    }
+
+    #[test]
+    fn rejects_wrong_scheme_flag() {
+        // 0x01 为 Secp256k1
+        let signature = modified_signature(|bytes| bytes[0] = 0x01);
+        assert!(!verify_personal_message(ADDRESS, MESSAGE, &signature));
+    }
+
+    #[test]
+    fn rejects_truncated_signature() {
+        let signature = modified_signature(|bytes| bytes.truncate(bytes.len() - 1));
+        assert!(!verify_personal_message(ADDRESS, MESSAGE, &signature));
+        assert!(!verify_personal_message(ADDRESS, MESSAGE, ""));
+        assert!(!verify_personal_message(ADDRESS, MESSAGE, "not base64!"));
+    }
+
+    #[test]
+    fn rejects_tampered_message_or_signature() {
+        assert!(!verify_personal_message(ADDRESS, b"Sign in to Learn2Earn\nNonce: 3f2a9d", SIGNATURE));
+        assert!(!verify_personal_message(ADDRESS, &[b'x'; 201], LONG_SIGNATURE));
+        let signature = modified_signature(|bytes| bytes[10] ^= 0x01);
+        assert!(!verify_personal_message(ADDRESS, MESSAGE, &signature));
+    }
+
+    #[test]
+    fn uleb128_encodes_lengths() {
+        assert_eq!(uleb128(0), vec![0x00]);
+        assert_eq!(uleb128(127), vec![0x7f]);
+        assert_eq!(uleb128(128), vec![0x80, 0x01]);
+        assert_eq!(uleb128(200), vec![0xc8, 0x01]);
+    }
+}
\ No newline at end of file
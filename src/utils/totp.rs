@@ -0,0 +1,164 @@
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, RngCore};
+use sha1::Sha1;
+
+// RFC 6238 默认参数，主流验证器 App 都支持
+pub const TOTP_PERIOD: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+// 允许前后各一个时间步的时钟偏差
+const ALLOWED_SKEW: i64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+// 160 位随机密钥
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; 20];
+    thread_rng().fill_bytes(&mut secret);
+    secret
+}
+
+// RFC 4648 base32，不带填充（otpauth URI 的格式）
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+// RFC 4226 HOTP
+fn hotp(secret: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+    binary % 10u32.pow(TOTP_DIGITS)
+}
+
+// 是否为 6 位数字验证码
+pub fn looks_like_code(code: &str) -> bool {
+    code.len() == TOTP_DIGITS as usize && code.bytes().all(|b| b.is_ascii_digit())
+}
+
+// 校验验证码，成功时返回匹配的时间步；不超过 last_used_step 的时间步视为重放
+pub fn verify(secret: &[u8], code: &str, unix_time: i64, last_used_step: Option<i64>) -> Option<i64> {
+    if !looks_like_code(code) {
+        return None;
+    }
+    let expected: u32 = code.parse().ok()?;
+    let current = unix_time.div_euclid(TOTP_PERIOD);
+    (current - ALLOWED_SKEW..=current + ALLOWED_SKEW)
+        .filter(|step| *step >= 0 && last_used_step.is_none_or(|last| *step > last))
+        .find(|step| hotp(secret, *step as u64) == expected)
+}
+
+// 供验证器 App 扫码的 otpauth URI
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        base32_encode(secret),
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_PERIOD
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            output.push(byte as char);
+        } else {
+            output.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4226 附录 D / RFC 6238 附录 B 使用的 SHA1 密钥
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    fn code_at(unix_time: i64) -> String {
+        format!("{:06}", hotp(RFC_SECRET, unix_time.div_euclid(TOTP_PERIOD) as u64))
+    }
+
+    #[test]
+    fn hotp_matches_rfc4226_vectors() {
+        let expected = [
+            755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489,
+        ];
+        for (counter, code) in expected.into_iter().enumerate() {
+            assert_eq!(hotp(RFC_SECRET, counter as u64), code, "counter {}", counter);
+        }
+    }
+
+    #[test]
+    fn verify_matches_rfc6238_vectors() {
+        // RFC 6238 的 8 位结果取后 6 位
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+            (20000000000, "353130"),
+        ];
+        for (unix_time, code) in vectors {
+            assert_eq!(verify(RFC_SECRET, code, unix_time, None), Some(unix_time / TOTP_PERIOD), "time {}", unix_time);
+        }
+    }
+
+    #[test]
+    fn verify_allows_one_step_of_skew() {
+        let now = 1_700_000_000;
+        let step = now / TOTP_PERIOD;
+        assert_eq!(verify(RFC_SECRET, &code_at(now - TOTP_PERIOD), now, None), Some(step - 1));
+        assert_eq!(verify(RFC_SECRET, &code_at(now + TOTP_PERIOD), now, None), Some(step + 1));
+        assert_eq!(verify(RFC_SECRET, &code_at(now - 2 * TOTP_PERIOD), now, None), None);
+        assert_eq!(verify(RFC_SECRET, &code_at(now + 2 * TOTP_PERIOD), now, None), None);
+    }
+
+    #[test]
+    fn verify_rejects_reused_time_step() {
+        let now = 1_700_000_000;
+        let step = now / TOTP_PERIOD;
+        let code = code_at(now);
+        assert_eq!(verify(RFC_SECRET, &code, now, Some(step - 1)), Some(step));
+        assert_eq!(verify(RFC_SECRET, &code, now, Some(step)), None);
+        // 已使用较新的时间步后，窗口内较早的验证码也不能再用
+        assert_eq!(verify(RFC_SECRET, &code_at(now - TOTP_PERIOD), now, Some(step)), None);
+    }
+
+    #[test]
+    fn verify_rejects_malformed_codes() {
+        for code in ["", "28708", "2870820", "28708a", " 287082"] {
+            assert_eq!(verify(RFC_SECRET, code, 59, None), None, "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn base32_matches_rfc4648_vectors() {
+        assert_eq!(base32_encode(b""), "");
+        assert_eq!(base32_encode(b"f"), "MY");
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_encode(RFC_SECRET), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    }
+}
\ No newline at end of file